@@ -10,7 +10,7 @@ pub struct ExampleComponent {
 
 impl<R: BufRead, W> Input<Repl<R, W>, String> for ExampleComponent {
     fn handle (&mut self, context: &mut Repl<R, W>) -> Result<Option<String>> {
-        self.state = context.read();
+        self.state = context.event.take().unwrap_or_default();
         Ok(None)
     }
 }
@@ -4,21 +4,21 @@ use std::io::Write;
 #[derive(Debug)]
 pub struct ExampleComponent {
     label: String,
-    input: String,
-    index: usize
+    input: TextInput,
+    entered: String
 }
 
 impl<W: Write> Input<TUI<W>, bool> for ExampleComponent {
     fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
-        if let Some(TUIInputEvent::Key(key)) = engine.event {
-            match key.code {
-                KeyCode::Esc => { engine.exit()?; },
-                KeyCode::Char(c) => { self.input.push(c); }
-                KeyCode::Backspace => { self.input.pop(); }
-                _ => {}
-            }
+        if let Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Esc, .. })) = engine.event {
+            engine.exit()?;
+            return Ok(None)
         }
-        Ok(None)
+        Ok(match self.input.handle(engine)? {
+            Some(TextInputMessage::Submit(value)) => { self.entered = value; Some(true) },
+            Some(TextInputMessage::Change(_)) => Some(true),
+            None => None
+        })
     }
 }
 
@@ -28,23 +28,22 @@ impl<W: Write> Output<TUI<W>, [u16;2]> for ExampleComponent {
             .add(Rows::new()
                 .add("Press Esc to quit  ")
                 .add("Arrows move cursor ")
-                .add("Shift to highlight "))
+                .add("Shift to highlight ")
+                .add("Up/Down for history"))
             .add(Rows::new()
                 .add(&self.label)
                 .add(&self.input)
-                .add(Layers::new()
-                    .add("String")
-                    .add(String::from("String"))))
+                .add(&self.entered))
             .render(engine)
     }
 }
 
 fn main () -> Result<()> {
 
-    let result = ExampleComponent {
-        label: "Enter some text:".to_string(),
-        input: "> ".to_string(),
-        index: 2
+    ExampleComponent {
+        label:   "Enter some text:".to_string(),
+        input:   TextInput::new().placeholder("Type here, then press Enter").history(20),
+        entered: String::new()
     }.run(TUI::stdio()?)?;
 
     Ok(())
@@ -16,8 +16,17 @@ impl Output<(), ()> for NullWidget {
     }
 }
 
-impl<'a, X: Input<(), ()> + Output<(), ()>> MainLoop<()> for X {
-    fn run (self, _: ()) -> Result<()> {
+/// The null engine renders nothing, handles no input, and exits right away.
+impl Context for () {
+    type Handled  = ();
+    type Rendered = ();
+    fn handle (&mut self, _: &mut impl Input<Self, Self::Handled>) -> Result<()> {
         Ok(())
     }
+    fn render (&mut self, _: &impl Output<Self, Self::Rendered>) -> Result<()> {
+        Ok(())
+    }
+    fn exited (&self) -> bool {
+        true
+    }
 }
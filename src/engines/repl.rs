@@ -3,25 +3,45 @@
 //! Renders the app to the terminal as a series of question/answer prompts.
 
 use crate::*;
-use std::io::{Stdout, Write, BufRead};
+use std::io::{Stdin, Stdout, Write, BufRead, BufReader};
+use std::future::{Future, poll_fn};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::task::Poll;
+use std::thread::spawn;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug)]
 pub struct Repl<R, W> {
-    input:  R,
+    /// Where lines are read from, until that's moved to a background thread
+    input:  Option<R>,
+    /// Lines read in the background, so that tasks can run while waiting for input
+    lines:  Option<Receiver<std::io::Result<String>>>,
+    /// Wakes the main loop when a line has been read in the background
+    wake:   WakeHandle,
     output: W,
-    pub exited: bool
+    pub exited: bool,
+    /// The line of input currently being handled
+    pub event: Option<String>,
+    /// Futures spawned by widgets
+    tasks: Tasks,
+    /// Width of the output, in characters
+    pub width: u16,
+    /// Currently available area. Output is laid out along lines,
+    /// so widths are padded with spaces, but heights aren't enforced.
+    pub area: [u16; 4]
 }
 
 impl<R: BufRead, W: Write> Context for Repl<R, W> {
     type Handled  = String;
     type Rendered = [u16;2];
     fn render (&mut self, engine: &impl Output<Self, Self::Rendered>) -> Result<()> {
+        self.area = [0, 0, self.width, u16::MAX];
         engine.render(self)?;
         self.output.flush()?;
         Ok(())
     }
-    fn handle (&mut self, _: &mut impl Input<Self, Self::Handled>) -> Result<()> {
-        self.read_line()?;
+    fn handle (&mut self, widget: &mut impl Input<Self, Self::Handled>) -> Result<()> {
+        self.event = Some(self.read_line()?);
+        widget.handle(self)?;
         Ok(())
     }
     fn exited (&self) -> bool {
@@ -29,44 +49,116 @@ impl<R: BufRead, W: Write> Context for Repl<R, W> {
     }
 }
 
+impl<R: BufRead + Send + 'static, W: Write> AsyncContext for Repl<R, W> {
+    /// Reading a line blocks, so while there are tasks, lines are read on a background thread.
+    /// Tasks are polled alongside it, rerendering after each one completes.
+    async fn handle_async (&mut self, widget: &mut impl Input<Self, Self::Handled>) -> Result<()> {
+        if self.tasks.is_empty() && self.lines.is_none() {
+            return self.handle(widget)
+        }
+        self.read_in_background();
+        let line = poll_fn(|cx| {
+            // Register before checking, so that lines read in between aren't missed
+            self.wake.register(cx.waker());
+            if self.tasks.poll(cx) > 0 {
+                return Poll::Ready(Ok(None))
+            }
+            match self.lines.as_ref().map(|lines|lines.try_recv()) {
+                Some(Ok(line))                 => Poll::Ready(line.map(Some)),
+                Some(Err(TryRecvError::Empty)) => Poll::Pending,
+                // Past the end of the input, like `read_line`
+                _                              => Poll::Ready(Ok(Some(String::new())))
+            }
+        }).await?;
+        if let Some(line) = line {
+            self.event = Some(line);
+            widget.handle(self)?;
+        }
+        Ok(())
+    }
+    fn spawn (&mut self, future: impl Future<Output = ()> + 'static) {
+        self.tasks.spawn(future)
+    }
+}
+
 impl<R, W: Write> Repl<R, W> {
     pub fn write (&mut self, data: &[u8]) -> Result<()> {
         self.output.write_all(data)?;
         Ok(())
     }
+    /// Write a number of spaces.
+    pub fn pad (&mut self, width: u16) -> Result<()> {
+        self.write(" ".repeat(width as usize).as_bytes())
+    }
+    /// Call `f` with the area temporarily set to `area`.
+    pub fn with_area <T> (&mut self, area: [u16;4], f: impl FnOnce(&mut Self)->T) -> T {
+        let outer = std::mem::replace(&mut self.area, area);
+        let result = f(self);
+        self.area = outer;
+        result
+    }
 }
 
 impl<R: BufRead, W> Repl<R, W> {
+    /// Create a REPL context reading lines from `input` and writing to `output`
+    fn new (input: R, output: W, width: u16) -> Self {
+        Self {
+            input: Some(input), lines: None, wake: WakeHandle::default(), output,
+            exited: false, event: None, tasks: Tasks::default(), width, area: [0; 4]
+        }
+    }
     fn read_line (&mut self) -> Result<String> {
-        let mut input = String::new();
-        self.input.read_line(&mut input)?;
-        Ok(input)
+        if let Some(lines) = &self.lines {
+            return Ok(lines.recv().unwrap_or_else(|_|Ok(String::new()))?)
+        }
+        let mut line = String::new();
+        if let Some(input) = &mut self.input {
+            input.read_line(&mut line)?;
+        }
+        Ok(line)
     }
 }
 
-impl Repl<std::io::StdinLock<'static>, Stdout> {
+impl<R: BufRead + Send + 'static, W> Repl<R, W> {
+    /// Move reading lines to a background thread, which wakes the main loop after each one.
+    fn read_in_background (&mut self) {
+        let Some(mut input) = self.input.take() else { return };
+        let (tx, lines) = channel();
+        let wake = self.wake.clone();
+        spawn(move || loop {
+            let mut line = String::new();
+            let result = input.read_line(&mut line);
+            let done = !matches!(result, Ok(length) if length > 0);
+            let sent = tx.send(result.map(|_|line)).is_ok();
+            wake.wake();
+            if done || !sent {
+                break
+            }
+        });
+        self.lines = Some(lines);
+    }
+}
+
+impl Repl<BufReader<Stdin>, Stdout> {
     /// Create a REPL context talking to the user over stdin/stdout
     pub fn stdio () -> Self {
-        let input  = std::io::stdin().lock();
-        let output = std::io::stdout();
-        Self { input, output, exited: false }
+        let width = crossterm::terminal::size().map(|(w, _)|w).unwrap_or(80);
+        Self::new(BufReader::new(std::io::stdin()), std::io::stdout(), width)
     }
 }
 
 /// A REPL context talking to the user over stdin/stdout
-pub type ReplStdio = Repl<std::io::StdinLock<'static>, Stdout>;
+pub type ReplStdio = Repl<BufReader<Stdin>, Stdout>;
 
-impl Repl<std::io::BufReader<&'static [u8]>, Vec<u8>> {
+impl Repl<BufReader<&'static [u8]>, Vec<u8>> {
     /// Create a REPL context taking predefined input and rendering to string
     pub fn harness (input: &'static [u8]) -> Self {
-        let input  = std::io::BufReader::new(input);
-        let output = vec![];
-        Self { input, output, exited: false }
+        Self::new(BufReader::new(input), vec![], 80)
     }
 }
 
 /// A REPL context taking predefined input and rendering to string
-pub type ReplHarness = Repl<std::io::BufReader<&'static [u8]>, Vec<u8>>;
+pub type ReplHarness = Repl<BufReader<&'static [u8]>, Vec<u8>>;
 
 #[cfg(test)]
 mod test {
@@ -83,4 +175,83 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn repl_should_read_input_while_tasks_run () -> Result<()> {
+        struct Quit;
+        impl<R: BufRead, W: Write> Input<Repl<R, W>, String> for Quit {
+            fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<String>> {
+                engine.exited = engine.event.as_deref() == Some("quit\n");
+                Ok(None)
+            }
+        }
+        impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for Quit {
+            fn render (&self, _: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+                Ok(None)
+            }
+        }
+        let mut engine = ReplHarness::harness("hello\nquit\n".as_bytes());
+        // A task that never finishes doesn't keep input from being handled
+        engine.spawn(std::future::pending());
+        let engine = block_on(Quit.run_async(engine))?;
+        assert_eq!(engine.event.as_deref(), Some("quit\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn repl_should_arrange () -> Result<()> {
+        use crate::layouts::*;
+        let app = Rows::new()
+            .add(Columns::new().add(Fixed::X(6, "name")).add(Fixed::X(5, Aligned(Align::Right, "1"))))
+            .add(Offset(2, 0, "indented"));
+        let mut engine = ReplHarness::harness("".as_bytes());
+        engine.render(&app)?;
+        assert_eq!(String::from_utf8(engine.output)?, "name      1\n  indented");
+        Ok(())
+    }
+
+    #[test]
+    fn repl_should_toggle () -> Result<()> {
+        use crate::layouts::*;
+        let mut toggle = Toggle::checkbox(false, "Dark mode");
+        let mut engine = ReplHarness::harness("".as_bytes());
+        engine.render(&toggle)?;
+        assert_eq!(String::from_utf8(engine.output.clone())?, "Dark mode [y/N] ");
+        engine.event = Some("maybe\n".into());
+        assert_eq!(toggle.handle(&mut engine)?, None);
+        engine.event = Some("yes\n".into());
+        assert_eq!(toggle.handle(&mut engine)?, Some(true));
+        engine.event = Some("y\n".into());
+        assert_eq!(toggle.handle(&mut engine)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn repl_should_number_list_items () -> Result<()> {
+        use crate::layouts::*;
+        let mut list = List::new(vec!["red", "green", "blue"]);
+        let mut engine = ReplHarness::harness("".as_bytes());
+        engine.render(&list)?;
+        assert_eq!(String::from_utf8(engine.output.clone())?, "* 1) red\n  2) green\n  3) blue");
+        engine.event = Some("3\n".into());
+        assert_eq!(list.handle(&mut engine)?, Some(2));
+        engine.event = Some("4\n".into());
+        assert_eq!(list.handle(&mut engine)?, None);
+        assert_eq!(list.selected(), Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn repl_should_align_table () -> Result<()> {
+        use crate::layouts::*;
+        let table = Table::new(vec![TableColumn::new("Name"), TableColumn::new("Size")], vec![
+            vec!["Cargo.toml".into(), "1.2K".into()],
+            vec!["src".into(), "-".into()],
+        ]);
+        let mut engine = ReplHarness::harness("".as_bytes());
+        engine.render(&table)?;
+        assert_eq!(String::from_utf8(engine.output.clone())?,
+            "Name       Size\nCargo.toml 1.2K\nsrc        -");
+        Ok(())
+    }
+
 }
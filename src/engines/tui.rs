@@ -2,12 +2,15 @@
 //!
 //! Renders the app to a terminal as an interactive text-based GUI (TUI).
 
-use crate::*;
+use crate::{*, layouts::Style};
 
 use ::crossterm::{
     ExecutableCommand,
     QueueableCommand,
     event::{
+        Event,
+        EnableMouseCapture,
+        DisableMouseCapture,
         poll,
         read
     },
@@ -15,6 +18,8 @@ use ::crossterm::{
         ResetColor,
         SetForegroundColor,
         SetBackgroundColor,
+        SetAttribute,
+        Attribute,
         Color,
         Print
     },
@@ -22,6 +27,7 @@ use ::crossterm::{
         Hide,
         Show,
         MoveTo,
+        position
     },
     terminal::{
         Clear,
@@ -35,16 +41,485 @@ use ::crossterm::{
 };
 
 pub use crossterm::{self, event::{
-    Event as TUIInputEvent,
     KeyEvent,
     KeyCode,
-    KeyModifiers
+    KeyModifiers,
+    MouseEvent,
+    MouseEventKind,
+    MouseButton
 }};
 
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}, mpsc::{channel, Sender, Receiver}};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use std::sync::{Arc, Once, atomic::{AtomicBool, AtomicU8, Ordering}, mpsc::{channel, Sender, Receiver, TryRecvError}};
+use std::collections::HashMap;
 use std::io::Write;
 use std::thread::spawn;
 use std::time::Duration;
+use std::future::{Future, poll_fn};
+use std::task::Poll;
+
+/// An event received by the main loop: either terminal input, or a timer firing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TUIInputEvent {
+    FocusGained,
+    FocusLost,
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    Paste(String),
+    Resize(u16, u16),
+    /// A timer set with `Context::set_timeout` or `Context::set_interval` has fired.
+    Tick(TimerId),
+}
+
+impl From<Event> for TUIInputEvent {
+    fn from (event: Event) -> Self {
+        match event {
+            Event::FocusGained   => Self::FocusGained,
+            Event::FocusLost     => Self::FocusLost,
+            Event::Key(key)      => Self::Key(key),
+            Event::Mouse(mouse)  => Self::Mouse(mouse),
+            Event::Paste(text)   => Self::Paste(text),
+            Event::Resize(w, h)  => Self::Resize(w, h),
+        }
+    }
+}
+
+/// Which part of the terminal the TUI draws in.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum TUIViewport {
+    /// The whole alternate screen, which is left on exit.
+    #[default] Fullscreen,
+    /// A number of lines below the cursor, which stay in the scrollback on exit.
+    Inline(u16),
+}
+
+/// Sends events to the main loop, waking it if it's waiting asynchronously.
+#[derive(Debug, Clone)]
+pub struct TUISender {
+    tx:   Sender<TUIInputEvent>,
+    wake: WakeHandle
+}
+
+impl TUISender {
+    pub fn send (&self, event: TUIInputEvent) -> Result<()> {
+        self.tx.send(event)?;
+        self.wake.wake();
+        Ok(())
+    }
+}
+
+/// How the terminal has been changed: whether it's been put in raw mode, and whether
+/// it's been switched to the alternate screen. Restoring it clears this, so that it's only
+/// restored once, and only what was changed is restored.
+#[derive(Debug, Default)]
+pub struct TerminalState(AtomicU8);
+
+impl TerminalState {
+
+    /// The terminal is in raw mode
+    pub const RAW_MODE: u8 = 1;
+
+    /// The terminal is on the alternate screen, with mouse capture enabled
+    pub const ALTERNATE_SCREEN: u8 = 2;
+
+    pub const fn new () -> Self {
+        Self(AtomicU8::new(0))
+    }
+
+    /// How the terminal has been changed and not yet restored
+    pub fn active (&self) -> u8 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Note that the terminal has been changed
+    pub fn activate (&self, change: u8) {
+        self.0.fetch_or(change, Ordering::SeqCst);
+    }
+
+    /// Clear the state, returning how the terminal needs restoring
+    pub fn take (&self) -> u8 {
+        self.0.swap(0, Ordering::SeqCst)
+    }
+
+    /// Show the cursor, leave the alternate screen and disable mouse capture if it was entered,
+    /// and leave raw mode with `disable_raw_mode`, unless that's already been done.
+    pub fn restore (
+        &self, output: &mut impl Write, disable_raw_mode: impl FnOnce()->std::io::Result<()>
+    ) -> Result<()> {
+        let changed = self.take();
+        if changed == 0 {
+            return Ok(())
+        }
+        output.execute(ResetColor)?.execute(Show)?;
+        if changed & Self::ALTERNATE_SCREEN != 0 {
+            output.execute(DisableMouseCapture)?.execute(LeaveAlternateScreen)?;
+        }
+        if changed & Self::RAW_MODE != 0 {
+            disable_raw_mode()?;
+        }
+        Ok(())
+    }
+
+    /// What the panic hook does: restore the terminal, then print the panic,
+    /// so that the message isn't lost on the alternate screen.
+    pub fn on_panic (
+        &self,
+        output:           &mut impl Write,
+        disable_raw_mode: impl FnOnce()->std::io::Result<()>,
+        print_panic:      impl FnOnce()
+    ) {
+        let _ = self.restore(output, disable_raw_mode);
+        print_panic()
+    }
+
+}
+
+/// The state of the terminal that the program runs in.
+static TERMINAL: TerminalState = TerminalState::new();
+
+/// Puts the terminal in raw mode, and restores it when dropped or when the program panics,
+/// so that an error or a panic doesn't leave it unusable. Owned by `TUI::stdio`.
+#[derive(Debug)]
+pub struct TerminalGuard(());
+
+impl TerminalGuard {
+
+    /// Enable raw mode, and make sure that a panic restores the terminal
+    /// before its message is printed.
+    pub fn new () -> Result<Self> {
+        Self::hook_panics();
+        enable_raw_mode()?;
+        TERMINAL.activate(TerminalState::RAW_MODE);
+        Ok(Self(()))
+    }
+
+    /// Note that the engine has switched to the alternate screen, so that it's left on restore.
+    /// In inline mode, it isn't, and the terminal stays on the main screen.
+    pub fn enter_alternate_screen (&self) {
+        TERMINAL.activate(TerminalState::ALTERNATE_SCREEN)
+    }
+
+    /// Restore the terminal before printing the message of a panic.
+    fn hook_panics () {
+        static PANIC_HOOK: Once = Once::new();
+        PANIC_HOOK.call_once(|| {
+            let print_panic = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                TERMINAL.on_panic(&mut std::io::stdout(), disable_raw_mode, ||print_panic(info))
+            }));
+        });
+    }
+
+    /// Leave raw mode, after the engine has restored the rest of the terminal.
+    pub fn release (self) -> Result<()> {
+        if TERMINAL.take() & TerminalState::RAW_MODE != 0 {
+            disable_raw_mode()?;
+        }
+        Ok(())
+    }
+
+}
+
+impl Drop for TerminalGuard {
+    fn drop (&mut self) {
+        let _ = TERMINAL.restore(&mut std::io::stdout(), disable_raw_mode);
+    }
+}
+
+/// Measures the achieved frame rate.
+#[cfg(feature = "fps_ticker")]
+pub struct FrameRate(pub fps_ticker::Fps);
+
+#[cfg(feature = "fps_ticker")]
+impl std::fmt::Debug for FrameRate {
+    fn fmt (&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "FrameRate({:.1})", self.0.avg())
+    }
+}
+
+/// Colors for the named roles that widgets draw with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Background of panels
+    pub background: Option<Color>,
+    /// Default text color
+    pub foreground: Option<Color>,
+    /// Highlights, e.g. toggles that are on
+    pub accent:     Option<Color>,
+    /// Inactive things, e.g. toggles that are off
+    pub muted:      Option<Color>,
+    /// The focused item
+    pub focus:      Option<Color>,
+    /// Error messages
+    pub error:      Option<Color>,
+    /// The lighter color of borders
+    pub border_hi:  Option<Color>,
+    /// The darker color of borders
+    pub border_lo:  Option<Color>,
+}
+
+impl Default for Theme {
+    /// Grey panels, for dark terminals
+    fn default () -> Self {
+        Self {
+            background: Some(Color::AnsiValue(235)),
+            foreground: None,
+            accent:     Some(Color::Green),
+            muted:      Some(Color::AnsiValue(240)),
+            focus:      Some(Color::Yellow),
+            error:      Some(Color::Red),
+            border_hi:  Some(Color::AnsiValue(240)),
+            border_lo:  Some(Color::AnsiValue(16)),
+        }
+    }
+}
+
+impl Theme {
+
+    /// How the selected item of a list, table or tree is highlighted.
+    pub fn selection (&self) -> Style {
+        Style { fg: self.focus, reverse: true, ..Style::default() }
+    }
+
+    /// Load a theme from a config file. See `Theme::parse`.
+    pub fn load (path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse a theme from lines of `role = color`, starting from the default theme.
+    /// Colors are names (`red`, `dark_grey`...), ANSI values (`235`),
+    /// hex RGB (`#ffcc00`), or `none` for the terminal's default.
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn parse (config: &str) -> Result<Self> {
+        let mut theme = Self::default();
+        for (number, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            let invalid = |message: &str|format!("theme, line {}: {message}: {line}", number + 1);
+            let (role, color) = line.split_once('=').ok_or_else(||invalid("expected `role = color`"))?;
+            let color = Self::parse_color(color.trim()).ok_or_else(||invalid("unknown color"))?;
+            *match role.trim() {
+                "background" => &mut theme.background,
+                "foreground" => &mut theme.foreground,
+                "accent"     => &mut theme.accent,
+                "muted"      => &mut theme.muted,
+                "focus"      => &mut theme.focus,
+                "error"      => &mut theme.error,
+                "border_hi"  => &mut theme.border_hi,
+                "border_lo"  => &mut theme.border_lo,
+                _ => return Err(invalid("unknown role").into())
+            } = color;
+        }
+        Ok(theme)
+    }
+
+    fn parse_color (color: &str) -> Option<Option<Color>> {
+        if color == "none" {
+            return Some(None)
+        }
+        if let Ok(value) = color.parse::<u8>() {
+            return Some(Some(Color::AnsiValue(value)))
+        }
+        if let Some(hex) = color.strip_prefix('#').filter(|hex|hex.len() == 6) {
+            let channel = |i: usize|u8::from_str_radix(hex.get(i..i+2)?, 16).ok();
+            return Some(Some(Color::Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? }))
+        }
+        Color::try_from(color).ok().map(Some)
+    }
+
+}
+
+/// How many colors the terminal can display.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// No colors, only the terminal's default ones
+    Monochrome,
+    /// The 16 named colors
+    Ansi16,
+    /// The 256-color palette
+    Ansi256,
+    /// 24-bit RGB
+    #[default] TrueColor,
+}
+
+/// Approximate RGB values of the 16 named colors, in palette order.
+const ANSI16_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),       (205, 0, 0),     (0, 205, 0),   (205, 205, 0),
+    (0, 0, 238),     (205, 0, 205),   (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0),     (0, 255, 0),   (255, 255, 0),
+    (92, 92, 255),   (255, 0, 255),   (0, 255, 255), (255, 255, 255),
+];
+
+/// The named colors, in palette order.
+const ANSI16: [Color; 16] = [
+    Color::Black,    Color::DarkRed, Color::DarkGreen, Color::DarkYellow,
+    Color::DarkBlue, Color::DarkMagenta, Color::DarkCyan, Color::Grey,
+    Color::DarkGrey, Color::Red,     Color::Green,     Color::Yellow,
+    Color::Blue,     Color::Magenta, Color::Cyan,      Color::White,
+];
+
+/// Levels of each channel in the 6x6x6 color cube of the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorSupport {
+
+    /// Detect what the terminal supports from the environment.
+    pub fn detect () -> Self {
+        Self::from_env(|name|std::env::var(name).ok())
+    }
+
+    /// Detect what the terminal supports from environment variables:
+    /// `NO_COLOR` disables colors, `COLORTERM` announces truecolor,
+    /// and `TERM` names the terminal type.
+    pub fn from_env (var: impl Fn(&str)->Option<String>) -> Self {
+        if var("NO_COLOR").is_some_and(|value|!value.is_empty()) {
+            return Self::Monochrome
+        }
+        if let Some("truecolor" | "24bit") = var("COLORTERM").as_deref() {
+            return Self::TrueColor
+        }
+        if var("WT_SESSION").is_some() {
+            // Windows Terminal
+            return Self::TrueColor
+        }
+        match var("TERM") {
+            None => Self::Ansi16,
+            Some(term) if term == "dumb" => Self::Monochrome,
+            Some(term) if term.ends_with("-direct") => Self::TrueColor,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            Some(_) => Self::Ansi16,
+        }
+    }
+
+    /// The nearest color that can be displayed, or `None` for the terminal's default.
+    pub fn convert (self, color: Color) -> Option<Color> {
+        match (self, color) {
+            (Self::Monochrome, _) => None,
+            (Self::TrueColor, color) => Some(color),
+            (Self::Ansi256, Color::Rgb { r, g, b }) => Some(Color::AnsiValue(ansi256(r, g, b))),
+            (Self::Ansi16, Color::Rgb { r, g, b }) => Some(ansi16(r, g, b)),
+            (Self::Ansi16, Color::AnsiValue(value)) => Some(match value {
+                0..=15 => ANSI16[value as usize],
+                _ => { let (r, g, b) = ansi256_rgb(value); ansi16(r, g, b) }
+            }),
+            (_, color) => Some(color),
+        }
+    }
+
+}
+
+/// Squared distance between two colors.
+fn distance ((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let channel = |a: u8, b: u8|(a as i32 - b as i32).pow(2) as u32;
+    channel(r1, r2) + channel(g1, g2) + channel(b1, b2)
+}
+
+/// The nearest of the 16 named colors.
+fn ansi16 (r: u8, g: u8, b: u8) -> Color {
+    let nearest = (0..16).min_by_key(|&index|distance(ANSI16_RGB[index], (r, g, b))).unwrap_or(0);
+    ANSI16[nearest]
+}
+
+/// The nearest color of the 256-color palette's color cube or grayscale ramp.
+fn ansi256 (r: u8, g: u8, b: u8) -> u8 {
+    let level = |value: u8|(0..6).min_by_key(|&index|(CUBE_LEVELS[index] as i32 - value as i32).abs())
+        .unwrap_or(0) as u8;
+    let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+    if distance(ansi256_rgb(gray), (r, g, b)) < distance(ansi256_rgb(cube), (r, g, b)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// The RGB value of a color of the 256-color palette.
+fn ansi256_rgb (value: u8) -> (u8, u8, u8) {
+    match value {
+        0..=15 => ANSI16_RGB[value as usize],
+        16..=231 => {
+            let index = value - 16;
+            (CUBE_LEVELS[(index / 36) as usize], CUBE_LEVELS[(index / 6 % 6) as usize], CUBE_LEVELS[(index % 6) as usize])
+        },
+        _ => {
+            let level = 8 + 10 * (value - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// The number of columns that a piece of text takes up on the screen.
+pub fn text_width (text: &str) -> u16 {
+    text.width().min(u16::MAX as usize) as u16
+}
+
+/// A character on the screen, with its colors and attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TUICell {
+    /// The text displayed in the cell: one grapheme cluster.
+    /// Empty if the cell is covered by the wide character to its left.
+    pub symbol: String,
+    /// Colors and attributes
+    pub style: Style,
+}
+
+impl Default for TUICell {
+    /// A blank cell, as left by clearing the screen
+    fn default () -> Self {
+        Self { symbol: " ".into(), style: Style::default() }
+    }
+}
+
+/// A screenful of cells, stored row by row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TUIGrid {
+    /// Width and height
+    pub size: [u16; 2],
+    cells: Vec<TUICell>,
+}
+
+impl TUIGrid {
+    /// Create a blank grid
+    pub fn new (size: [u16; 2]) -> Self {
+        Self { size, cells: vec![TUICell::default(); size[0] as usize * size[1] as usize] }
+    }
+    /// Blank all cells
+    pub fn clear (&mut self) {
+        self.cells.fill(TUICell::default())
+    }
+    /// Get the cell at the given position
+    pub fn get (&self, x: u16, y: u16) -> Option<&TUICell> {
+        if x < self.size[0] && y < self.size[1] {
+            self.cells.get(y as usize * self.size[0] as usize + x as usize)
+        } else {
+            None
+        }
+    }
+    /// Replace the cell at the given position, if it's within the grid.
+    /// Wide characters that are partially overwritten are blanked.
+    pub fn set (&mut self, x: u16, y: u16, cell: TUICell) {
+        if x >= self.size[0] || y >= self.size[1] {
+            return
+        }
+        let index = y as usize * self.size[0] as usize + x as usize;
+        if self.cells[index].symbol.is_empty() && !cell.symbol.is_empty() && x > 0 {
+            self.cells[index - 1].symbol = " ".into();
+        }
+        if x + 1 < self.size[0] && self.cells[index + 1].symbol.is_empty() {
+            self.cells[index + 1].symbol = " ".into();
+        }
+        self.cells[index] = cell
+    }
+    /// The text of a row, without styles
+    pub fn row (&self, y: u16) -> String {
+        (0..self.size[0]).filter_map(|x|self.get(x, y)).map(|cell|cell.symbol.as_str()).collect()
+    }
+}
 
 /// An instance of an app hosted by crossterm.
 #[derive(Debug)]
@@ -55,10 +530,58 @@ pub struct TUI<W: Write> {
     input: Receiver<TUIInputEvent>,
     /// Currently handled input event
     pub event: Option<TUIInputEvent>,
+    /// An event that was received while coalescing resizes, to be handled next.
+    pending: Option<TUIInputEvent>,
+    /// Size of the terminal, as reported by the last resize event.
+    /// Until there is one, it is queried on every render.
+    screen: Option<[u16; 2]>,
     /// Output. Terminal commands are written to this.
     pub output: W,
+    /// Colors that widgets draw with.
+    pub theme: Theme,
+    /// Colors that the terminal can display. Others are replaced with the nearest one.
+    pub colors: ColorSupport,
+    /// Text that widgets have copied, to be pasted into others.
+    pub clipboard: String,
+    /// What is currently on the screen.
+    front: TUIGrid,
+    /// What is being drawn. On flush, the cells that differ from `front` are output.
+    back: TUIGrid,
+    /// Style with which `put` draws.
+    style: Style,
+    /// Style that the terminal is currently set to.
+    terminal_style: Style,
     /// Currently available screen area.
-    pub area: [u16; 4]
+    pub area: [u16; 4],
+    /// Which part of the terminal is drawn in.
+    viewport: TUIViewport,
+    /// The screen row at which the viewport starts.
+    origin: u16,
+    /// Whether the next iteration of the main loop should render.
+    dirty: bool,
+    /// Sends events to the main loop. Used by timers.
+    sender: TUISender,
+    /// Futures spawned by widgets.
+    tasks: Tasks,
+    /// Timers, which send `Tick` events.
+    timers: Timers,
+    /// Position of the widget currently being rendered or handled,
+    /// as a list of indices into the containers that enclose it.
+    path: Vec<usize>,
+    /// The area that each widget occupied during the last render, by path.
+    rects: HashMap<Vec<usize>, [u16; 4]>,
+    /// Screen areas outside of which nothing is drawn, innermost last.
+    /// Each one lies within the previous one.
+    clips: Vec<[u16; 4]>,
+    /// Distance by which drawing is moved up and to the left. Set by scrollable viewports.
+    scroll: [u16; 2],
+    /// Area that the last rendered widget asked to be kept in view.
+    revealed: Option<[u16; 4]>,
+    /// Restores the terminal when the context is dropped.
+    guard: Option<TerminalGuard>,
+    /// Frame rate of the render loop.
+    #[cfg(feature = "fps_ticker")]
+    pub fps: FrameRate
 }
 
 impl<W: Write> Context for TUI<W> {
@@ -66,38 +589,275 @@ impl<W: Write> Context for TUI<W> {
     type Rendered = [u16;2];
 
     fn setup (&mut self) -> Result<()> {
-        self.output.execute(EnterAlternateScreen)?.execute(Hide)?;
+        match self.viewport {
+            TUIViewport::Fullscreen => {
+                self.output.execute(EnterAlternateScreen)?.execute(Hide)?.execute(EnableMouseCapture)?;
+                if let Some(guard) = &self.guard {
+                    guard.enter_alternate_screen();
+                }
+            },
+            TUIViewport::Inline(lines) => {
+                // Make room below the cursor, scrolling the screen up if needed
+                self.output.queue(Hide)?.queue(Print("\r\n".repeat(lines as usize)))?.flush()?;
+                self.origin = position()?.1.saturating_sub(lines);
+            }
+        }
         Ok(())
     }
 
+    /// Pass the next input event to the widget.
+    /// Widgets return `Some` when the event has changed their state;
+    /// this, or a resize, schedules a redraw.
     fn handle (&mut self, widget: &mut impl Input<Self, bool>) -> Result<()> {
-        self.event = Some(self.input.recv()?);
-        widget.handle(self)?;
-        Ok(())
+        let event = match self.pending.take() {
+            Some(event) => event,
+            None => self.input.recv()?
+        };
+        self.dispatch(event, widget)
     }
 
+    /// Draw the widget into the back buffer, then output the cells that changed.
     fn render (&mut self, widget: &impl Output<Self, [u16;2]>) -> Result<()> {
-        self.clear()?;
-        let (w, h) = size()?;
+        let [w, h] = match self.screen {
+            Some(screen) => screen,
+            None => { let (w, h) = size()?; [w, h] }
+        };
+        let [w, h] = self.fit_viewport([w, h]);
         self.area = [0, 0, w, h];
+        if self.front.size != [w, h] {
+            // On the first frame or after a resize, start from a blank screen
+            self.clear()?;
+            self.front = TUIGrid::new([w, h]);
+            self.back = TUIGrid::new([w, h]);
+        }
+        self.back.clear();
+        self.style = Style { fg: self.theme.foreground, ..Style::default() };
+        self.path.clear();
+        self.rects.clear();
+        self.rects.insert(vec![], self.area);
+        self.clips.clear();
+        self.scroll = [0, 0];
+        self.revealed = None;
         if let Err(error) = widget.render(self) {
             self.write_error(format!("{error}").as_str())?;
         }
-        // Flush output buffer
-        self.output.flush()?;
+        self.flush()?;
+        self.dirty = false;
+        #[cfg(feature = "fps_ticker")]
+        self.fps.0.tick();
         Ok(())
     }
 
     fn exited (&self) -> bool {
         self.exited.fetch_and(true, Ordering::Relaxed)
     }
+
+    fn dirty (&self) -> bool {
+        self.dirty
+    }
+
+    fn request_redraw (&mut self) {
+        self.dirty = true
+    }
+
+    /// Send a `Tick` event once, after `delay` has passed.
+    fn set_timeout (&mut self, delay: Duration) -> Result<TimerId> {
+        Ok(self.timers.set(delay, false))
+    }
+
+    /// Send a `Tick` event every `period`, until the timer is cleared.
+    fn set_interval (&mut self, period: Duration) -> Result<TimerId> {
+        Ok(self.timers.set(period, true))
+    }
+
+    fn clear_timer (&mut self, id: TimerId) {
+        self.timers.clear(id);
+    }
+
+    /// Pass a mouse event only to the item that was rendered under the cursor,
+    /// with the area set to that item's area; pass other events via `dispatch`.
+    /// Items are tried in order, so the topmost of overlapping `Layers` wins.
+    fn route <'a, U> (&mut self, items: &mut [Collected<'a, Self, U>], selected: Option<usize>)
+        -> Result<Option<U>>
+    {
+        let (column, row) = match self.event {
+            Some(TUIInputEvent::Mouse(MouseEvent { column, row, .. })) => (column, row),
+            _ => return crate::layouts::dispatch(items, selected, self)
+        };
+        let area = self.area;
+        for (index, item) in items.iter_mut().enumerate() {
+            if let Some(rect) = self.enter(index).hit(column, row) {
+                self.area = rect;
+                let result = item.handle(self);
+                self.leave().area = area;
+                return result
+            }
+            self.leave();
+        }
+        Ok(None)
+    }
+}
+
+impl<W: Write> AsyncContext for TUI<W> {
+
+    async fn handle_async (&mut self, widget: &mut impl Input<Self, bool>) -> Result<()> {
+        let event = poll_fn(|cx| {
+            // Register before checking, so that events sent in between aren't missed
+            self.sender.wake.register(cx.waker());
+            if let Some(event) = self.pending.take() {
+                return Poll::Ready(Ok(Some(event)))
+            }
+            if self.tasks.poll(cx) > 0 {
+                return Poll::Ready(Ok(None))
+            }
+            match self.input.try_recv() {
+                Ok(event)                => Poll::Ready(Ok(Some(event))),
+                Err(TryRecvError::Empty) => Poll::Pending,
+                Err(error)               => Poll::Ready(Err(error))
+            }
+        }).await?;
+        match event {
+            Some(event) => self.dispatch(event, widget),
+            None => {
+                self.dirty = true;
+                Ok(())
+            }
+        }
+    }
+
+    fn spawn (&mut self, future: impl Future<Output = ()> + 'static) {
+        self.tasks.spawn(future)
+    }
+
 }
 
 impl<W: Write> TUI<W> {
 
+    /// Create a TUI context from an output and a channel of input events.
+    fn new (output: W, input: Receiver<TUIInputEvent>, sender: TUISender) -> Self {
+        Self {
+            exited: Arc::new(AtomicBool::new(false)),
+            input,
+            event: None,
+            pending: None,
+            screen: None,
+            output,
+            theme: Theme::default(),
+            colors: ColorSupport::default(),
+            clipboard: String::new(),
+            front: TUIGrid::default(),
+            back: TUIGrid::default(),
+            style: Style::default(),
+            terminal_style: Style::default(),
+            area: [0, 0, 0, 0],
+            viewport: TUIViewport::Fullscreen,
+            origin: 0,
+            dirty: true,
+            timers: Timers::new({
+                let sender = sender.clone();
+                move |id|sender.send(TUIInputEvent::Tick(id)).is_ok()
+            }),
+            sender,
+            tasks: Tasks::default(),
+            path: vec![],
+            rects: HashMap::new(),
+            clips: vec![],
+            scroll: [0, 0],
+            revealed: None,
+            guard: None,
+            #[cfg(feature = "fps_ticker")]
+            fps: FrameRate(fps_ticker::Fps::default())
+        }
+    }
+
+    /// Draw in a number of lines below the cursor, instead of taking over the whole screen.
+    /// On exit, the last frame is left in place.
+    pub fn inline (mut self, lines: u16) -> Self {
+        self.viewport = TUIViewport::Inline(lines);
+        self
+    }
+
+    /// The size of the viewport on a screen of the given size.
+    /// An inline viewport is moved up if it no longer fits.
+    fn fit_viewport (&mut self, [w, h]: [u16; 2]) -> [u16; 2] {
+        match self.viewport {
+            TUIViewport::Fullscreen => [w, h],
+            TUIViewport::Inline(lines) => {
+                let lines = lines.min(h);
+                self.origin = self.origin.min(h - lines);
+                [w, lines]
+            }
+        }
+    }
+
+    /// Pass an input event to the widget.
+    fn dispatch (&mut self, event: TUIInputEvent, widget: &mut impl Input<Self, bool>) -> Result<()> {
+        match event {
+            TUIInputEvent::Resize(w, h) => {
+                return self.resize(w, h, widget)
+            },
+            // Forget timeouts once they've fired, and ignore timers that were cleared
+            TUIInputEvent::Tick(id) if !self.timers.fired(id) => {
+                return Ok(())
+            },
+            _ => {}
+        }
+        // Start from the root widget, which occupies the whole screen
+        self.path.clear();
+        if let Some(area) = self.rects.get(&self.path) {
+            self.area = *area;
+        }
+        self.event = Some(event);
+        if widget.handle(self)?.is_some() {
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Handle a burst of resize events at once, passing only the last one to the widget.
+    /// The layout recorded during the last render no longer applies, so it's discarded,
+    /// and the screen is redrawn from scratch.
+    fn resize (&mut self, mut w: u16, mut h: u16, widget: &mut impl Input<Self, bool>) -> Result<()> {
+        while let Ok(event) = self.input.try_recv() {
+            match event {
+                TUIInputEvent::Resize(next_w, next_h) => (w, h) = (next_w, next_h),
+                event => {
+                    self.pending = Some(event);
+                    break
+                }
+            }
+        }
+        self.screen = Some([w, h]);
+        let [w, h] = self.fit_viewport([w, h]);
+        self.area = [0, 0, w, h];
+        self.rects.clear();
+        self.rects.insert(vec![], self.area);
+        self.front = TUIGrid::default();
+        self.dirty = true;
+        self.path.clear();
+        self.event = Some(TUIInputEvent::Resize(w, h));
+        widget.handle(self)?;
+        Ok(())
+    }
+
+    /// Restore the terminal to how it was before the app started.
     pub fn cleanup (&mut self) -> Result<()> {
-        self.output.execute(ResetColor)?.execute(Show)?.execute(LeaveAlternateScreen)?;
-        disable_raw_mode()?;
+        match self.viewport {
+            TUIViewport::Fullscreen => {
+                self.output.execute(ResetColor)?.execute(Show)?.execute(DisableMouseCapture)?
+                    .execute(LeaveAlternateScreen)?;
+            },
+            TUIViewport::Inline(_) => {
+                // Leave the cursor on the line below the last frame
+                let last = (self.origin + self.front.size[1]).saturating_sub(1);
+                self.output.queue(ResetColor)?.queue(MoveTo(0, last))?.queue(Print("\r\n"))?
+                    .execute(Show)?;
+            }
+        }
+        // Only an engine that entered raw mode leaves it
+        if let Some(guard) = self.guard.take() {
+            guard.release()?;
+        }
         Ok(())
     }
 
@@ -107,34 +867,155 @@ impl<W: Write> TUI<W> {
         Ok(())
     }
 
-    /// Clear the screen
+    /// Clear the screen, or, if inline, everything from the start of the viewport down.
     fn clear (&mut self) -> Result<()> {
-        self.output.queue(ResetColor)?.queue(Clear(ClearType::All))?.queue(Hide)?;
+        self.output.queue(ResetColor)?;
+        match self.viewport {
+            TUIViewport::Fullscreen =>
+                self.output.queue(Clear(ClearType::All))?,
+            TUIViewport::Inline(_) =>
+                self.output.queue(MoveTo(0, self.origin))?.queue(Clear(ClearType::FromCursorDown))?
+        }.queue(Hide)?;
+        self.terminal_style = Style::default();
+        Ok(())
+    }
+
+    /// Output the cells of the back buffer that differ from what's on the screen,
+    /// then make the back buffer the front one. Short runs of unchanged cells
+    /// between changed ones are output too, as that's cheaper than moving the cursor.
+    fn flush (&mut self) -> Result<()> {
+        let width = self.back.size[0].max(1) as usize;
+        let mut cursor = None;
+        for index in 0..self.back.cells.len().min(self.front.cells.len()) {
+            if self.back.cells[index] == self.front.cells[index] {
+                continue
+            }
+            if self.back.cells[index].symbol.is_empty() {
+                // Covered by the wide character to the left, which was printed if changed
+                continue
+            }
+            let (x, y) = (index % width, index / width);
+            let start = match cursor {
+                Some(next) if next <= index && index - next <= 4 && next / width == y => next,
+                _ => {
+                    self.output.queue(MoveTo(x as u16, self.origin + y as u16))?;
+                    index
+                }
+            };
+            for index in start..=index {
+                self.print(index)?;
+            }
+            let mut next = index + 1;
+            while next < self.back.cells.len() && self.back.cells[next].symbol.is_empty() {
+                next += 1
+            }
+            cursor = Some(next);
+        }
+        std::mem::swap(&mut self.front, &mut self.back);
+        self.output.flush()?;
+        Ok(())
+    }
+
+    /// Output a cell of the back buffer at the cursor position.
+    fn print (&mut self, index: usize) -> Result<()> {
+        let TUICell { symbol, style } = &self.back.cells[index];
+        if *style != self.terminal_style {
+            self.output.queue(ResetColor)?;
+            if let Some(fg) = style.fg.and_then(|fg|self.colors.convert(fg)) {
+                self.output.queue(SetForegroundColor(fg))?;
+            }
+            if let Some(bg) = style.bg.and_then(|bg|self.colors.convert(bg)) {
+                self.output.queue(SetBackgroundColor(bg))?;
+            }
+            for (enabled, attribute) in [
+                (style.bold,      Attribute::Bold),
+                (style.italic,    Attribute::Italic),
+                (style.underline, Attribute::Underlined),
+                (style.reverse,   Attribute::Reverse),
+                (style.dim,       Attribute::Dim),
+            ] {
+                if enabled {
+                    self.output.queue(SetAttribute(attribute))?;
+                }
+            }
+            self.terminal_style = *style;
+        }
+        self.output.queue(Print(symbol))?;
         Ok(())
     }
 
-    /// Write some text to the terminal.
+    /// Write some text to the back buffer.
+    /// Inside a scrollable viewport, the text is moved by the scroll offset.
+    /// The parts of the text that fall outside the current clip are not drawn.
     pub fn put (&mut self, x: u16, y: u16, text: &impl std::fmt::Display) -> Result<&mut Self> {
-        self.output.queue(MoveTo(x, y))?.queue(Print(text))?;
+        let x = x as i32 - self.scroll[0] as i32;
+        let y = y as i32 - self.scroll[1] as i32;
+        let [cx, cy, cw, ch] = self.clip();
+        let [cx, cy, cw, ch] = [cx as i32, cy as i32, cw as i32, ch as i32];
+        if y < cy || y >= cy + ch {
+            return Ok(self)
+        }
+        let style = self.style;
+        let cell = |symbol: &str|TUICell { symbol: symbol.into(), style };
+        let mut x = x;
+        for symbol in text.to_string().graphemes(true) {
+            let width = symbol.width() as i32;
+            if width == 0 {
+                continue
+            }
+            if x >= cx && x + width <= cx + cw {
+                self.back.set(x as u16, y as u16, cell(symbol));
+                for column in x + 1..x + width {
+                    self.back.set(column as u16, y as u16, cell(""));
+                }
+            } else {
+                // Blank the visible part of a wide character that straddles the clip
+                for column in x.max(cx)..(x + width).min(cx + cw) {
+                    self.back.set(column as u16, y as u16, cell(" "));
+                }
+            }
+            x += width;
+        }
         Ok(self)
     }
 
-    pub fn set_colors (&mut self, fg: &Option<Color>, bg: &Option<Color>) -> Result<&mut Self> {
-        self.output.queue(ResetColor)?;
-        if let Some(fg) = fg {
-            self.output.queue(SetForegroundColor(*fg))?;
-        }
-        if let Some(bg) = bg {
-            self.output.queue(SetBackgroundColor(*bg))?;
+    /// Blank the visible part of an area of the back buffer.
+    pub fn blank (&mut self, area: [u16;4]) -> &mut Self {
+        let [x, y, w, h] = self.to_screen(area);
+        for row in y..y + h {
+            for column in x..x + w {
+                self.back.set(column, row, TUICell::default());
+            }
         }
+        self
+    }
+
+    /// Set the colors with which the following text is drawn, clearing other attributes.
+    pub fn set_colors (&mut self, fg: &Option<Color>, bg: &Option<Color>) -> Result<&mut Self> {
+        self.style = Style { fg: *fg, bg: *bg, ..Style::default() };
         Ok(self)
     }
 
+    /// Set the style with which the following text is drawn.
+    pub fn set_style (&mut self, style: Style) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    /// Call `f` with `style` applied on top of the current style.
+    pub fn with_style <T> (&mut self, style: Style, f: impl FnOnce(&mut Self)->T) -> T {
+        let outer = self.style;
+        self.style = outer.patch(style);
+        let result = f(self);
+        self.style = outer;
+        result
+    }
+
     /// Write some red text to the terminal.
     fn write_error (&mut self, msg: &str) -> Result<&mut Self> {
-        self.clear()?;
-        self.output.queue(SetForegroundColor(Color::Red))?;
-        self.put(0, 0, &msg)
+        self.back.clear();
+        let error = self.theme.error;
+        self.set_colors(&error, &None)?.put(0, 0, &msg)
     }
 
     pub fn area (&mut self, alter_area: impl Fn(&[u16;4])->[u16;4]) -> &mut Self {
@@ -142,6 +1023,134 @@ impl<W: Write> TUI<W> {
         self
     }
 
+    /// Call `f` with the area temporarily set to `area`.
+    pub fn with_area <T> (&mut self, area: [u16;4], f: impl FnOnce(&mut Self)->T) -> T {
+        let outer = std::mem::replace(&mut self.area, area);
+        let result = f(self);
+        self.area = outer;
+        result
+    }
+
+    /// Call `f` with the area temporarily set to `area`, and drawing clipped to it.
+    pub fn with_clipped_area <T> (&mut self, area: [u16;4], f: impl FnOnce(&mut Self)->T) -> T {
+        self.with_clip(area, |engine|engine.with_area(area, f))
+    }
+
+    /// The screen area outside of which nothing is currently drawn.
+    pub fn clip (&self) -> [u16;4] {
+        self.clips.last().copied().unwrap_or([0, 0, self.back.size[0], self.back.size[1]])
+    }
+
+    /// Narrow the clip to the visible part of `area`.
+    pub fn push_clip (&mut self, area: [u16;4]) -> &mut Self {
+        let clip = self.to_screen(area);
+        self.clips.push(clip);
+        self
+    }
+
+    /// Restore the previous clip.
+    pub fn pop_clip (&mut self) -> &mut Self {
+        self.clips.pop();
+        self
+    }
+
+    /// Call `f` with drawing clipped to `area`.
+    pub fn with_clip <T> (&mut self, area: [u16;4], f: impl FnOnce(&mut Self)->T) -> T {
+        let result = f(self.push_clip(area));
+        self.pop_clip();
+        result
+    }
+
+    /// Call `f` with drawing clipped to `area`, and moved by `offset`.
+    /// Viewports nest: the clip narrows, and the offsets add up.
+    pub fn with_viewport <T> (
+        &mut self, area: [u16;4], offset: [u16;2], f: impl FnOnce(&mut Self)->T
+    ) -> T {
+        self.push_clip(area);
+        let outer_scroll = self.scroll;
+        self.scroll = [
+            self.scroll[0].saturating_add(offset[0]), self.scroll[1].saturating_add(offset[1])
+        ];
+        let result = f(self);
+        self.scroll = outer_scroll;
+        self.pop_clip();
+        result
+    }
+
+    /// Convert an area from layout coordinates to the visible part of it on the screen.
+    fn to_screen (&self, [x, y, w, h]: [u16;4]) -> [u16;4] {
+        let x0 = x as i32 - self.scroll[0] as i32;
+        let y0 = y as i32 - self.scroll[1] as i32;
+        let (x1, y1) = (x0 + w as i32, y0 + h as i32);
+        let [cx, cy, cw, ch] = self.clip();
+        let [cx, cy, cw, ch] = [cx as i32, cy as i32, cw as i32, ch as i32];
+        let (x0, y0) = (x0.max(cx), y0.max(cy));
+        let (x1, y1) = (x1.min(cx + cw).max(x0), y1.min(cy + ch).max(y0));
+        [x0 as u16, y0 as u16, (x1 - x0) as u16, (y1 - y0) as u16]
+    }
+
+    /// Ask for an area to be kept in view by the enclosing scrollable viewport.
+    pub fn reveal (&mut self, area: [u16;4]) -> &mut Self {
+        self.revealed = Some(area);
+        self
+    }
+
+    /// Take the area that was last asked to be kept in view.
+    pub fn take_revealed (&mut self) -> Option<[u16;4]> {
+        self.revealed.take()
+    }
+
+    /// Descend into the item at `index` of the current container.
+    pub fn enter (&mut self, index: usize) -> &mut Self {
+        self.path.push(index);
+        self
+    }
+
+    /// Return to the enclosing container.
+    pub fn leave (&mut self) -> &mut Self {
+        self.path.pop();
+        self
+    }
+
+    /// Record the area occupied by the current widget, for hit-testing.
+    /// Only the part of it that is visible on the screen is recorded.
+    pub fn record (&mut self, rect: [u16;4]) -> &mut Self {
+        let rect = self.to_screen(rect);
+        self.rects.insert(self.path.clone(), rect);
+        self
+    }
+
+    /// If the current widget was rendered under the given point, return its area.
+    pub fn hit (&self, x: u16, y: u16) -> Option<[u16;4]> {
+        self.rects.get(&self.path).copied().filter(|[rx, ry, rw, rh]|
+            x >= *rx && x < rx + rw && y >= *ry && y < ry + rh
+        )
+    }
+
+    /// If the current event is a mouse event, return it,
+    /// with coordinates relative to the current area.
+    pub fn mouse (&self) -> Option<(MouseEventKind, [u16;2])> {
+        match self.event {
+            Some(TUIInputEvent::Mouse(MouseEvent { kind, column, row, .. })) => Some((
+                kind, [column.saturating_sub(self.area[0]), row.saturating_sub(self.area[1])]
+            )),
+            _ => None
+        }
+    }
+
+    /// If the current event is a left click inside a rectangle of the given size
+    /// at the top left of the current area, return its position relative to the area.
+    pub fn click (&self, [w, h]: [u16;2]) -> Option<[u16;2]> {
+        let [x, y, _, _] = self.area;
+        match self.event {
+            Some(TUIInputEvent::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left), column, row, ..
+            })) if column >= x && column - x < w && row >= y && row - y < h =>
+                Some([column - x, row - y]),
+            _ => None
+        }
+    }
+
 }
 
 type TUIStdio = TUI<std::io::Stdout>;
@@ -151,11 +1160,14 @@ impl TUIStdio {
     /// Create a TUI context for talking to the user over stdin/stdout.
     pub fn stdio () -> Result<Self> {
         let output = std::io::stdout();
-        enable_raw_mode()?;
+        let guard = TerminalGuard::new()?;
         let (tx, input) = channel::<TUIInputEvent>();
-        let exited = Arc::new(AtomicBool::new(false));
+        let sender = TUISender { tx, wake: WakeHandle::default() };
+        let mut engine = Self::new(output, input, sender.clone());
+        engine.guard = Some(guard);
+        engine.colors = ColorSupport::detect();
         // Spawn the input thread
-        let exit_input_thread = exited.clone();
+        let exit_input_thread = engine.exited.clone();
         spawn(move || {
             loop {
                 // Exit if flag is set
@@ -163,27 +1175,31 @@ impl TUIStdio {
                     break
                 }
                 // Listen for events and send them to the main thread
-                if poll(Duration::from_millis(100)).is_ok() {
-                    if tx.send(read().unwrap()).is_err() {
+                if let Ok(true) = poll(Duration::from_millis(100)) {
+                    if sender.send(read().unwrap().into()).is_err() {
                         break
                     }
                 }
             }
         });
-        Ok(Self { exited, input, event: None, output, area: [0, 0, 0, 0] })
+        Ok(engine)
     }
 
 }
 
-type TUIHarness = TUI<Vec<u8>>;
+/// A TUI context taking predefined input and rendering to a buffer
+pub type TUIHarness = TUI<Vec<u8>>;
 
 impl TUIHarness {
     /// Create a TUI context that takes predefined input and renders to a buffer
-    pub fn harness () -> (Self, Sender<TUIInputEvent>) {
-        let output = vec![];
-        let exited = Arc::new(AtomicBool::new(false));
+    pub fn harness () -> (Self, TUISender) {
         let (tx, input) = channel::<TUIInputEvent>();
-        (Self { exited, input, event: None, output, area: [0, 0, 0, 0] }, tx)
+        let sender = TUISender { tx, wake: WakeHandle::default() };
+        (Self::new(vec![], input, sender.clone()), sender)
+    }
+    /// The cells that were output by the last render
+    pub fn grid (&self) -> &TUIGrid {
+        &self.front
     }
 }
 
@@ -202,7 +1218,7 @@ impl TUIHarness {
 #[macro_export] macro_rules! match_key {
     (($event:expr) { $($code:expr => $block:block),+ }) => {
         {
-            if let Event::Key(event) = $event {
+            if let $crate::engines::tui::TUIInputEvent::Key(event) = $event {
                 $(if event.code == $code $block else)* { false }
             } else {
                 false
@@ -214,9 +1230,21 @@ impl TUIHarness {
 #[cfg(test)]
 mod test {
 
-    use crate::{MainLoop, layouts::*, engines::tui::*};
+    use crate::{MainLoop, AsyncMainLoop, AsyncContext, Context, Collection, block_on, layouts::*, engines::tui::*};
     use std::{error::Error, sync::atomic::Ordering};
 
+    /// A key press
+    fn key (code: KeyCode, modifiers: KeyModifiers) -> TUIInputEvent {
+        TUIInputEvent::Key(KeyEvent::new(code, modifiers))
+    }
+
+    /// A left click at a position on the screen
+    fn click (column: u16, row: u16) -> TUIInputEvent {
+        TUIInputEvent::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left), column, row, modifiers: KeyModifiers::empty()
+        })
+    }
+
     #[test]
     fn tui_should_run () -> Result<()> {
         let app = String::from("just a label");
@@ -228,9 +1256,651 @@ mod test {
         }
         let output = String::from_utf8(app.run(engine)?.output)?;
         //let prefix = "\u{1b}[?1049h\u{1b}[?25l\u{1b}[0m\u{1b}[2J\u{1b}[?25l\u{1b}[1;1H";
-        let prefix = "\u{1b}[0m\u{1b}[?25h\u{1b}[?1049l\u{1b}[?1049h\u{1b}[?25l\u{1b}[0m\u{1b}[2J\u{1b}[?25l\u{1b}[1;1H";
+        let prefix = concat!(
+            "\u{1b}[0m\u{1b}[?25h",
+            "\u{1b}[?1006l\u{1b}[?1015l\u{1b}[?1003l\u{1b}[?1002l\u{1b}[?1000l",
+            "\u{1b}[?1049l\u{1b}[?1049h\u{1b}[?25l",
+            "\u{1b}[?1000h\u{1b}[?1002h\u{1b}[?1003h\u{1b}[?1015h\u{1b}[?1006h",
+            "\u{1b}[0m\u{1b}[2J\u{1b}[?25l\u{1b}[1;1H"
+        );
         assert_eq!(output, format!("{prefix}just a label"));
         Ok(())
     }
 
+    #[test]
+    fn tui_should_output_changed_cells () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.render(&"hello")?;
+        assert_eq!(&engine.grid().row(0)[..6], "hello ");
+        engine.output.clear();
+        engine.render(&"help!")?;
+        assert_eq!(&engine.grid().row(0)[..6], "help! ");
+        assert_eq!(String::from_utf8(engine.output.clone())?, "\u{1b}[1;4Hp!");
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_clip_to_area () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.render(&Columns::new().add(Fixed::X(3, "hello")).add(Offset(1, 0, "world")))?;
+        assert_eq!(&engine.grid().row(0)[..10], "hel world ");
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_flex_columns () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([12, 1]);
+        engine.render(&Columns::new()
+            .add(Flexed(Flex::Percent(25), "ab"))
+            .add(Flexed(Flex::Fill, "cd"))
+            .add(Flexed(Flex::Ratio(1, 4), "ef")))?;
+        assert_eq!(engine.grid().row(0), "ab cd    ef ");
+        // An oversized share takes up the whole row, and pushes the rest out of view
+        engine.render(&Columns::new().add(Flexed(Flex::Percent(150), "gh")).add("ij"))?;
+        assert_eq!(engine.grid().row(0), "gh          ");
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_measure_display_width () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.render(&Columns::new().add("中文").add("é!"))?;
+        assert_eq!(engine.grid().row(0).trim_end(), "中文é!");
+        // A wide character that doesn't fit is blanked
+        engine.render(&Columns::new().add(Fixed::X(3, "中文")).add("a"))?;
+        assert_eq!(engine.grid().row(0).trim_end(), "中 a");
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_draw_styles () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        let bold = Style::new().fg(Color::Red).bold();
+        engine.render(&Line::new().span("a", bold).span("b", Style::new()))?;
+        assert_eq!(engine.grid().get(0, 0).unwrap().style, bold);
+        assert_eq!(engine.grid().get(1, 0).unwrap().style, Style::new());
+        assert!(String::from_utf8(engine.output.clone())?.ends_with(
+            "\u{1b}[1;1H\u{1b}[0m\u{1b}[38;5;9m\u{1b}[1ma\u{1b}[0mb"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_render_on_demand () -> Result<()> {
+        struct Counter(std::rc::Rc<std::cell::Cell<usize>>);
+        impl<W: Write> Input<TUI<W>, bool> for Counter {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                Ok(match engine.event {
+                    Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Esc, .. })) => {
+                        engine.exit()?;
+                        None
+                    },
+                    Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Enter, .. })) => Some(true),
+                    _ => None
+                })
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Counter {
+            fn render (&self, _: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                self.0.set(self.0.get() + 1);
+                Ok(None)
+            }
+        }
+        let (engine, sender) = TUI::harness();
+        for code in [KeyCode::Char('a'), KeyCode::Enter, KeyCode::Char('b'), KeyCode::Esc] {
+            sender.send(key(code, KeyModifiers::empty()))?;
+        }
+        let renders = std::rc::Rc::new(std::cell::Cell::new(0));
+        Counter(renders.clone()).run(engine)?;
+        assert_eq!(renders.get(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_run_async () -> Result<()> {
+        let app = String::from("just a label");
+        let (mut engine, _sender) = TUI::harness();
+        let done = std::rc::Rc::new(std::cell::Cell::new(false));
+        let exit = engine.exited.clone();
+        let task_done = done.clone();
+        engine.spawn(async move {
+            task_done.set(true);
+            exit.store(true, Ordering::Relaxed);
+        });
+        block_on(app.run_async(engine))?;
+        assert!(done.get());
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_tick () -> Result<()> {
+        struct Clock(u8);
+        impl<W: Write> Input<TUI<W>, bool> for Clock {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                if let Some(TUIInputEvent::Tick(_)) = engine.event {
+                    self.0 += 1;
+                    if self.0 == 3 {
+                        engine.exit()?;
+                    }
+                    return Ok(Some(true))
+                }
+                Ok(None)
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Clock {
+            fn render (&self, _: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                Ok(None)
+            }
+        }
+        let (mut engine, _sender) = TUI::harness();
+        let timer = engine.set_interval(Duration::from_millis(1))?;
+        let engine = Clock(0).run(engine)?;
+        assert!(engine.timers.contains(timer));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_match_key () {
+        let matched = |event: &TUIInputEvent|crate::match_key!((event) {
+            KeyCode::Enter => { true },
+            KeyCode::Esc   => { true }
+        });
+        assert!(matched(&key(KeyCode::Enter, KeyModifiers::empty())));
+        assert!(matched(&key(KeyCode::Esc, KeyModifiers::empty())));
+        assert!(!matched(&key(KeyCode::Tab, KeyModifiers::empty())));
+        assert!(!matched(&TUIInputEvent::FocusGained));
+    }
+
+    #[test]
+    fn tui_should_route_mouse_events () -> Result<()> {
+        struct Button(&'static str, Option<[u16;2]>);
+        impl<W: Write> Input<TUI<W>, bool> for Button {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                Ok(match engine.mouse() {
+                    Some((MouseEventKind::Down(_), position)) => {
+                        self.1 = Some(position);
+                        Some(true)
+                    },
+                    _ => None
+                })
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Button {
+            fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                self.0.render(engine)
+            }
+        }
+        struct Buttons([Button; 2]);
+        impl<W: Write> Input<TUI<W>, bool> for Buttons {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                let [a, b] = &mut self.0;
+                Rows::new().add_mut(a).add_mut(b).handle(engine)
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Buttons {
+            fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                Rows::new().add(&self.0[0]).add(&self.0[1]).render(engine)
+            }
+        }
+        let mut app = Buttons([Button("first", None), Button("second", None)]);
+        let (mut engine, sender) = TUI::harness();
+        engine.render(&app)?;
+        sender.send(click(3, 1))?;
+        engine.handle(&mut app)?;
+        assert_eq!(app.0[0].1, None);
+        assert_eq!(app.0[1].1, Some([3, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_route_clicks_into_tabs () -> Result<()> {
+        struct Page(Option<[u16;2]>);
+        impl<W: Write> Input<TUI<W>, bool> for Page {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                self.0 = engine.click([5, 1]);
+                Ok(self.0.map(|_|true))
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Page {
+            fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                "page".render(engine)
+            }
+        }
+        struct App(Tabbed<Page>);
+        impl<W: Write> Input<TUI<W>, bool> for App {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                Rows::new().add_mut(&mut self.0).handle(engine)
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for App {
+            fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                Rows::new().add(self.0.layout(Style::default())).render(engine)
+            }
+        }
+        let (mut engine, sender) = TUI::harness();
+        engine.screen = Some([10, 4]);
+        let mut tabs = Tabbed::top(vec![("one".into(), Page(None)), ("two".into(), Page(None))]);
+        tabs.focus = Some(1);
+        let mut app = App(tabs);
+        engine.render(&app)?;
+        assert_eq!(engine.grid().row(3), "page      ");
+        // Clicks on the tab labels don't reach the page
+        sender.send(click(1, 0))?;
+        engine.handle(&mut app)?;
+        assert_eq!(app.0.pages[1].1.0, None);
+        // Clicks inside the page are relative to it
+        sender.send(click(2, 3))?;
+        engine.handle(&mut app)?;
+        assert_eq!(app.0.pages[1].1.0, Some([2, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_align () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([5, 3]);
+        let aligned = |align|Aligned(align, "ab");
+        // An aligned item takes up the whole area, and places its content within it
+        let size = engine.with_area([0, 0, 5, 3], |engine|aligned(Align::Center).measure(engine))?;
+        assert_eq!(size, Some([5, 3]));
+        for (align, position) in [
+            (Align::TopLeft,    [0, 0]), (Align::Top,    [1, 0]), (Align::TopRight,    [3, 0]),
+            (Align::Left,       [0, 1]), (Align::Center, [1, 1]), (Align::Right,       [3, 1]),
+            (Align::BottomLeft, [0, 2]), (Align::Bottom, [1, 2]), (Align::BottomRight, [3, 2]),
+        ] {
+            engine.render(&aligned(align))?;
+            let rows: Vec<String> = (0..3).map(|row|engine.grid().row(row)).collect();
+            let [x, y] = position;
+            let mut expected = vec![String::from("     "); 3];
+            expected[y].replace_range(x..x+2, "ab");
+            assert_eq!(rows, expected, "{align:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_constrain_size () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([5, 2]);
+        let area = [0, 0, 5, 2];
+        // A minimum size is reported, but the content stays within the parent's area
+        assert_eq!(Min::X(8, "abc").inner(area), area);
+        assert_eq!(engine.with_area(area, |engine|Min::X(8, "abc").measure(engine))?, Some([8, 1]));
+        // A maximum size limits both the content's area and the reported size
+        assert_eq!(Max::X(2, "abc").inner(area), [0, 0, 2, 2]);
+        assert_eq!(engine.with_area(area, |engine|Max::X(2, "abc").measure(engine))?, Some([2, 1]));
+        engine.render(&Max::X(2, "abc"))?;
+        assert_eq!(engine.grid().row(0), "ab   ");
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_scroll_overflowing_content () -> Result<()> {
+        struct Tall(std::cell::Cell<usize>);
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Tall {
+            fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                self.0.set(self.0.get() + 1);
+                let [x, y, _, _] = engine.area;
+                for row in 0..10 {
+                    engine.put(x, y + row, &format!("{row}bc"))?;
+                }
+                engine.reveal([x, y + 7, 3, 1]);
+                self.measure(engine)
+            }
+            fn measure (&self, _: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                Ok(Some([3, 10]))
+            }
+        }
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([4, 5]);
+        let scroll = Scrollable::new(Tall(Default::default()));
+        // The content is rendered again only when the viewport moves to follow it
+        engine.render(&scroll)?;
+        assert_eq!(scroll.content.0.replace(0), 2);
+        engine.render(&scroll)?;
+        assert_eq!(scroll.content.0.replace(0), 1);
+        let rows: Vec<String> = (0..5).map(|row|engine.grid().row(row)).collect();
+        assert_eq!(rows, ["3bc│", "4bc█", "5bc█", "6bc│", "7bc│"]);
+        // Content that overflows both axes of a 1 column wide viewport leaves no room for it
+        engine.screen = Some([1, 5]);
+        engine.render(&scroll)?;
+        let rows: Vec<String> = (0..5).map(|row|engine.grid().row(row)).collect();
+        assert_eq!(rows, ["│", "█", "│", "│", " "]);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_toggle () -> Result<()> {
+        let (mut engine, sender) = TUI::harness();
+        engine.screen = Some([12, 1]);
+        let mut toggle = Toggle::checkbox(false, "Wrap");
+        engine.render(&toggle)?;
+        assert_eq!(engine.grid().row(0), "[ ] Wrap    ");
+        assert_ne!(engine.grid().get(1, 0).unwrap().style.fg, engine.theme.accent);
+        // Space and Enter flip it
+        for code in [KeyCode::Char(' '), KeyCode::Enter, KeyCode::Char(' ')] {
+            sender.send(key(code, KeyModifiers::empty()))?;
+            engine.handle(&mut toggle)?;
+        }
+        assert!(toggle.0);
+        engine.render(&toggle)?;
+        assert_eq!(engine.grid().row(0), "[x] Wrap    ");
+        assert_eq!(engine.grid().get(1, 0).unwrap().style.fg, engine.theme.accent);
+        // So does a click on it
+        sender.send(click(5, 0))?;
+        engine.handle(&mut toggle)?;
+        assert!(!toggle.0);
+        engine.render(&toggle)?;
+        assert_eq!(engine.grid().row(0), "[ ] Wrap    ");
+        // Flipping it asks for a redraw, even if its message doesn't reach the root
+        assert!(!engine.dirty());
+        engine.event = Some(key(KeyCode::Enter, KeyModifiers::empty()));
+        toggle.handle(&mut engine)?;
+        assert!(engine.dirty());
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_restore_terminal_once () -> Result<()> {
+        // A harness leaves alone the terminal of a real engine
+        let active = TERMINAL.active();
+        let (mut engine, _) = TUI::harness();
+        engine.setup()?;
+        engine.cleanup()?;
+        assert!(String::from_utf8(engine.output.clone())?.ends_with("\u{1b}[?1049l"));
+        assert_eq!(TERMINAL.active(), active);
+        // The terminal is restored once, however many times that's attempted
+        let state = TerminalState::new();
+        let disabled = std::cell::Cell::new(0);
+        let disable = ||{ disabled.set(disabled.get() + 1); Ok(()) };
+        state.activate(TerminalState::RAW_MODE | TerminalState::ALTERNATE_SCREEN);
+        let mut output = vec![];
+        state.restore(&mut output, disable)?;
+        state.restore(&mut output, disable)?;
+        assert!(String::from_utf8(output)?.ends_with("\u{1b}[?1049l"));
+        assert_eq!(disabled.get(), 1);
+        // Inline, the alternate screen was never entered, so it isn't left
+        let mut output = vec![];
+        state.activate(TerminalState::RAW_MODE);
+        state.restore(&mut output, disable)?;
+        assert_eq!(String::from_utf8(output)?, "\u{1b}[0m\u{1b}[?25h");
+        assert_eq!(disabled.get(), 2);
+        // On panic, the terminal is restored before the panic is printed
+        let mut output = vec![];
+        let mut printed = None;
+        state.activate(TerminalState::RAW_MODE | TerminalState::ALTERNATE_SCREEN);
+        state.on_panic(&mut output, disable, ||printed = Some((state.active(), disabled.get())));
+        assert_eq!(printed, Some((0, 3)));
+        assert!(String::from_utf8(output)?.ends_with("\u{1b}[?1049l"));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_parse_theme () -> Result<()> {
+        let theme = Theme::parse("# Light\nbackground = 255\nforeground = black\naccent = #00aa00\nfocus=none\nmuted = 250\n")?;
+        assert_eq!(theme.background, Some(Color::AnsiValue(255)));
+        assert_eq!(theme.foreground, Some(Color::Black));
+        assert_eq!(theme.accent, Some(Color::Rgb { r: 0, g: 0xaa, b: 0 }));
+        assert_eq!(theme.focus, None);
+        assert_eq!(theme.muted, Some(Color::AnsiValue(250)));
+        assert_eq!(theme.error, Theme::default().error);
+        assert!(Theme::parse("shadow = red").is_err());
+        assert!(Theme::parse("accent = #12345").is_err());
+        // Widgets draw with the engine's theme
+        let (mut engine, _) = TUI::harness();
+        engine.theme = Theme { accent: Some(Color::Blue), ..theme };
+        engine.render(&Toggle::checkbox(true, "on"))?;
+        assert!(String::from_utf8_lossy(&engine.output).contains("\u{1b}[38;5;12m[x]"));
+        engine.theme.muted = Some(Color::Magenta);
+        engine.render(&Toggle::checkbox(false, "off"))?;
+        assert_eq!(engine.grid().get(1, 0).unwrap().style.fg, Some(Color::Magenta));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_coalesce_resizes () -> Result<()> {
+        struct Resizable(Vec<[u16;4]>);
+        impl<W: Write> Input<TUI<W>, bool> for Resizable {
+            fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+                if let Some(TUIInputEvent::Resize(_, _)) = engine.event {
+                    self.0.push(engine.area);
+                }
+                Ok(None)
+            }
+        }
+        impl<W: Write> Output<TUI<W>, [u16;2]> for Resizable {
+            fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+                engine.put(0, 0, &"x")?;
+                Ok(Some([1, 1]))
+            }
+        }
+        let (mut engine, sender) = TUI::harness();
+        let mut widget = Resizable(vec![]);
+        for width in 10..20 {
+            sender.send(TUIInputEvent::Resize(width, 3))?;
+        }
+        sender.send(key(KeyCode::Esc, KeyModifiers::empty()))?;
+        engine.handle(&mut widget)?;
+        assert_eq!(widget.0, vec![[0, 0, 19, 3]]);
+        assert!(engine.dirty());
+        engine.render(&widget)?;
+        assert_eq!(engine.grid().size, [19, 3]);
+        // The event after the burst is handled next
+        engine.handle(&mut widget)?;
+        assert_eq!(engine.event, Some(key(KeyCode::Esc, KeyModifiers::empty())));
+        // Shrinking the terminal redraws from a blank screen
+        engine.output.clear();
+        sender.send(TUIInputEvent::Resize(5, 2))?;
+        engine.handle(&mut widget)?;
+        engine.render(&widget)?;
+        assert!(String::from_utf8_lossy(&engine.output).contains("\u{1b}[2J"));
+        assert_eq!(engine.grid().size, [5, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_render_inline () -> Result<()> {
+        let (engine, _) = TUI::harness();
+        let mut engine = engine.inline(3);
+        engine.screen = Some([10, 20]);
+        engine.origin = 18;
+        engine.render(&"hello")?;
+        // The viewport is moved up to fit, and the rest of the screen is left alone
+        assert_eq!(engine.grid().size, [10, 3]);
+        assert_eq!(engine.area, [0, 0, 10, 3]);
+        let output = String::from_utf8_lossy(&engine.output).to_string();
+        assert!(output.starts_with("\u{1b}[0m\u{1b}[18;1H\u{1b}[J"));
+        assert!(output.ends_with("\u{1b}[18;1Hhello"));
+        // On exit, the last frame is kept, and the cursor goes below it
+        engine.output.clear();
+        engine.cleanup()?;
+        let output = String::from_utf8_lossy(&engine.output).to_string();
+        assert!(output.contains("\u{1b}[20;1H\r\n"));
+        assert!(!output.contains("\u{1b}[?1049l"));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_downsample_colors () -> Result<()> {
+        let env = |vars: &'static [(&'static str, &'static str)]|ColorSupport::from_env(move |name|
+            vars.iter().find(|(var, _)|*var == name).map(|(_, value)|value.to_string()));
+        assert_eq!(env(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]), ColorSupport::TrueColor);
+        assert_eq!(env(&[("TERM", "xterm-256color")]), ColorSupport::Ansi256);
+        assert_eq!(env(&[("TERM", "linux")]), ColorSupport::Ansi16);
+        assert_eq!(env(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]), ColorSupport::Monochrome);
+        let orange = Color::Rgb { r: 255, g: 135, b: 0 };
+        assert_eq!(ColorSupport::Ansi256.convert(orange), Some(Color::AnsiValue(208)));
+        assert_eq!(ColorSupport::Ansi256.convert(Color::Rgb { r: 30, g: 30, b: 30 }), Some(Color::AnsiValue(234)));
+        assert_eq!(ColorSupport::Ansi16.convert(Color::AnsiValue(240)), Some(Color::DarkGrey));
+        assert_eq!(ColorSupport::Ansi16.convert(Color::AnsiValue(9)), Some(Color::Red));
+        assert_eq!(ColorSupport::Monochrome.convert(Color::Red), None);
+        // Colors are converted when output
+        let (mut engine, _) = TUI::harness();
+        engine.colors = ColorSupport::Ansi16;
+        engine.screen = Some([1, 1]);
+        engine.render(&"a".style(Style::new().fg(orange)))?;
+        assert!(String::from_utf8_lossy(&engine.output).ends_with("\u{1b}[38;5;3ma"));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_edit_text_input () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([6, 1]);
+        let mut input = TextInput::new().placeholder("name");
+        engine.render(&input)?;
+        assert_eq!(engine.grid().row(0).trim_end(), "name");
+        for c in "hello world".chars() {
+            engine.event = Some(key(KeyCode::Char(c), KeyModifiers::empty()));
+            input.handle(&mut engine)?;
+        }
+        // Scrolled so that the cursor stays in view
+        engine.render(&input)?;
+        assert_eq!(engine.grid().row(0), "world ");
+        // Edits ask for a redraw, even if their message doesn't reach the root
+        engine.event = Some(key(KeyCode::Char('!'), KeyModifiers::empty()));
+        input.handle(&mut engine)?;
+        assert!(engine.dirty());
+        engine.event = Some(key(KeyCode::Backspace, KeyModifiers::empty()));
+        input.handle(&mut engine)?;
+        engine.event = Some(key(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::SHIFT));
+        assert_eq!(input.handle(&mut engine)?, None);
+        assert_eq!(input.selected(), "world");
+        engine.event = Some(key(KeyCode::Backspace, KeyModifiers::empty()));
+        assert_eq!(input.handle(&mut engine)?, Some(TextInputMessage::Change("hello ".into())));
+        engine.event = Some(key(KeyCode::Enter, KeyModifiers::empty()));
+        assert_eq!(input.handle(&mut engine)?, Some(TextInputMessage::Submit("hello ".into())));
+        assert_eq!(input.value, "");
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_edit_text_area () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([8, 2]);
+        let mut area = TextArea::new().text("one two three\nfour");
+        engine.render(&area)?;
+        assert_eq!(engine.grid().row(0), "one two ");
+        assert_eq!(engine.grid().row(1), "three   ");
+        for (code, modifiers) in [
+            (KeyCode::Down,     KeyModifiers::empty()),
+            (KeyCode::Down,     KeyModifiers::empty()),
+            (KeyCode::End,      KeyModifiers::SHIFT),
+            (KeyCode::Char('x'), KeyModifiers::CONTROL),
+            (KeyCode::Home,     KeyModifiers::CONTROL),
+            (KeyCode::Char('v'), KeyModifiers::CONTROL),
+        ] {
+            engine.event = Some(key(code, modifiers));
+            area.handle(&mut engine)?;
+        }
+        assert_eq!(engine.clipboard, "four");
+        assert_eq!(area.value, "fourone two three\n");
+        // Copying or cutting nothing keeps the clipboard
+        for code in [KeyCode::Char('c'), KeyCode::Char('x')] {
+            engine.event = Some(key(code, KeyModifiers::CONTROL));
+            area.handle(&mut engine)?;
+        }
+        assert_eq!(engine.clipboard, "four");
+        engine.render(&area)?;
+        engine.event = Some(key(KeyCode::Char('z'), KeyModifiers::CONTROL));
+        assert_eq!(area.handle(&mut engine)?, Some(TextInputMessage::Change("one two three\n".into())));
+        assert!(engine.dirty());
+        // At the end of a row that fills the width, the cursor stays visible
+        let mut area = TextArea::new().text("one two!");
+        engine.event = Some(key(KeyCode::End, KeyModifiers::CONTROL));
+        area.handle(&mut engine)?;
+        engine.render(&area)?;
+        assert_eq!(engine.grid().row(0), "one two!");
+        assert_eq!(engine.grid().get(7, 0).map(|cell|cell.style.reverse), Some(true));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_scroll_list_to_selection () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([5, 2]);
+        let mut list = List::new(vec!["a", "b", "c", "d"]);
+        engine.render(&list)?;
+        assert_eq!(engine.grid().get(0, 0).map(|cell|cell.style.reverse), Some(true));
+        engine.event = Some(key(KeyCode::End, KeyModifiers::empty()));
+        assert_eq!(list.handle(&mut engine)?, Some(3));
+        engine.render(&list)?;
+        assert_eq!([engine.grid().row(0), engine.grid().row(1)], ["c    ", "d    "]);
+        // Click on an item to select it
+        engine.event = Some(click(2, 0));
+        assert_eq!(list.handle(&mut engine)?, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_sort_table_on_header_click () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([12, 3]);
+        let mut table = Table::new(vec![
+            TableColumn::new("Name"),
+            TableColumn::new("Size").width(ColumnWidth::Fixed(4)),
+        ], vec![
+            vec!["beta".into(), "20".into()],
+            vec!["alphabet".into(), "3".into()],
+            vec!["gamma".into(), "100".into()],
+        ]);
+        engine.render(&table)?;
+        assert_eq!(engine.grid().row(0), "Name    Size");
+        assert_eq!(engine.grid().row(1), "beta    20  ");
+        // The same row stays selected, and its new index is returned
+        engine.event = Some(click(9, 0));
+        assert_eq!(table.handle(&mut engine)?, Some(1));
+        assert_eq!(table.sort, Some((1, true)));
+        engine.render(&table)?;
+        assert_eq!(engine.grid().row(0), "Name    Siz…");
+        assert_eq!(engine.grid().row(1), "alphab… 3   ");
+        assert_eq!(engine.grid().get(0, 2).map(|cell|cell.style.reverse), Some(true));
+        // Clicks below the last visible row, or above or left of the table, are ignored
+        engine.event = Some(click(0, 3));
+        assert_eq!(table.handle(&mut engine)?, None);
+        engine.area = [2, 1, 10, 3];
+        for (column, row) in [(5, 0), (0, 2)] {
+            engine.event = Some(click(column, row));
+            assert_eq!(table.handle(&mut engine)?, None);
+        }
+        assert_eq!((table.sort, table.selected()), (Some((1, true)), Some(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn tui_should_browse_tree () -> Result<()> {
+        let (mut engine, _) = TUI::harness();
+        engine.screen = Some([12, 4]);
+        let mut tree = TreeView::new(vec![
+            TreeNode::new("src", vec![TreeNode::leaf("lib.rs"), TreeNode::leaf("main.rs")]),
+            TreeNode::leaf("README"),
+        ]);
+        engine.event = Some(key(KeyCode::Right, KeyModifiers::empty()));
+        assert_eq!(tree.handle(&mut engine)?, None);
+        engine.event = Some(key(KeyCode::Right, KeyModifiers::empty()));
+        assert_eq!(tree.handle(&mut engine)?, Some(vec![0, 0]));
+        engine.render(&tree)?;
+        let rows: Vec<String> = (0..4).map(|row|engine.grid().row(row).trim_end().to_string()).collect();
+        assert_eq!(rows, ["▾ src", "├── lib.rs", "└── main.rs", "  README"]);
+        // Left goes to the parent, then collapses it
+        engine.event = Some(key(KeyCode::Left, KeyModifiers::empty()));
+        assert_eq!(tree.handle(&mut engine)?, Some(vec![0]));
+        engine.event = Some(key(KeyCode::Left, KeyModifiers::empty()));
+        tree.handle(&mut engine)?;
+        assert_eq!(tree.len(), 2);
+        // A click on a marker toggles its node, and clicks below the visible rows are ignored
+        engine.screen = Some([12, 2]);
+        engine.render(&tree)?;
+        engine.event = Some(click(0, 0));
+        assert_eq!(tree.handle(&mut engine)?, None);
+        assert_eq!(tree.len(), 4);
+        engine.render(&tree)?;
+        engine.event = Some(click(0, 2));
+        assert_eq!(tree.handle(&mut engine)?, None);
+        engine.event = Some(click(5, 1));
+        assert_eq!(tree.handle(&mut engine)?, Some(vec![0, 0]));
+        Ok(())
+    }
+
 }
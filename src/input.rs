@@ -1,4 +1,5 @@
-use crate::*;
+use crate::{*, layouts::Flex};
+use std::marker::PhantomData;
 
 /// Updates state in response to user input, in an engine-specific way.
 pub trait Input<T, U> {
@@ -16,3 +17,96 @@ impl<T, U, V: Input<T, U>> Input<T, U> for dyn Proxy<V> {
         self.get_mut().handle(engine)
     }
 }
+
+/// Trait for a root widget that applies the messages emitted by its descendants,
+/// Elm-style, instead of its descendants holding references to app state.
+pub trait Update<T, U> {
+    /// Apply a message that bubbled up from this widget's `handle` method.
+    fn update (&mut self, message: U, engine: &mut T) -> Result<()>;
+}
+
+/// Wraps the root widget, passing the messages it emits to its `update` method,
+/// and requesting a redraw after each one.
+#[derive(Debug)]
+pub struct Root<X, U>(pub X, PhantomData<U>);
+
+impl<X, U> Root<X, U> {
+    pub fn new (widget: X) -> Self {
+        Self(widget, PhantomData)
+    }
+}
+
+impl<T: Context, U, X: Input<T, U> + Update<T, U>> Input<T, T::Handled> for Root<X, U> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<T::Handled>> {
+        if let Some(message) = self.0.handle(engine)? {
+            self.0.update(message, engine)?;
+            engine.request_redraw();
+        }
+        Ok(None)
+    }
+}
+
+impl<T, U, V, X: Output<T, V>> Output<T, V> for Root<X, U> {
+    fn render (&self, engine: &mut T) -> Result<Option<V>> {
+        self.0.render(engine)
+    }
+    fn measure (&self, engine: &mut T) -> Result<Option<V>> {
+        self.0.measure(engine)
+    }
+    fn flex (&self) -> Flex {
+        self.0.flex()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{*, layouts::*, engines::repl::*};
+    use std::io::{BufRead, Write};
+
+    #[derive(Debug, PartialEq)]
+    enum Message { Submitted(String) }
+
+    struct Prompt;
+
+    impl<R: BufRead, W: Write> Input<Repl<R, W>, Message> for Prompt {
+        fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<Message>> {
+            Ok(engine.event.take().map(|line|Message::Submitted(line.trim().into())))
+        }
+    }
+
+    struct App { prompt: Fixed<u16, Offset<u16, Prompt>>, submitted: Vec<String> }
+
+    impl<R: BufRead, W: Write> Input<Repl<R, W>, Message> for App {
+        fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<Message>> {
+            self.prompt.handle(engine)
+        }
+    }
+
+    impl<R: BufRead, W: Write> Update<Repl<R, W>, Message> for App {
+        fn update (&mut self, message: Message, engine: &mut Repl<R, W>) -> Result<()> {
+            let Message::Submitted(text) = message;
+            self.submitted.push(text);
+            engine.exited = true;
+            Ok(())
+        }
+    }
+
+    impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for App {
+        fn render (&self, _: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn should_bubble_messages_to_root () -> Result<()> {
+        let app = App { prompt: Fixed::X(10, Offset(1, 1, Prompt)), submitted: vec![] };
+        let mut root = Root::new(app);
+        let mut engine = ReplHarness::harness("hello\n".as_bytes());
+        engine.handle(&mut root)?;
+        assert_eq!(root.0.submitted, vec!["hello".to_string()]);
+        assert!(engine.exited);
+        Ok(())
+    }
+
+}
@@ -26,8 +26,28 @@ pub struct Aligned<T>(
     pub T
 );
 
-impl<T, U, V: Output<T, U>> Output<T, U> for Aligned<V> {
-    fn render (&self, context: &mut T) -> Result<Option<U>> {
-        self.1.render(context)
+impl Align {
+    /// Place an item of the given size inside an area.
+    pub fn place (&self, area: [u16;4], size: [u16;2]) -> [u16;4] {
+        let [x, y, w, h] = area;
+        let [item_w, item_h] = [size[0].min(w), size[1].min(h)];
+        let (free_w, free_h) = (w - item_w, h - item_h);
+        let dx = match self {
+            Self::TopLeft    | Self::Left   | Self::BottomLeft  => 0,
+            Self::Top        | Self::Center | Self::Bottom      => free_w / 2,
+            Self::TopRight   | Self::Right  | Self::BottomRight => free_w,
+        };
+        let dy = match self {
+            Self::TopLeft    | Self::Top    | Self::TopRight    => 0,
+            Self::Left       | Self::Center | Self::Right       => free_h / 2,
+            Self::BottomLeft | Self::Bottom | Self::BottomRight => free_h,
+        };
+        [x + dx, y + dy, item_w, item_h]
+    }
+}
+
+impl<T, U, V: Input<T, U>> Input<T, U> for Aligned<V> {
+    fn handle (&mut self, context: &mut T) -> Result<Option<U>> {
+        self.1.handle(context)
     }
 }
@@ -1,11 +1,15 @@
 use crate::*;
+use super::*;
 
 /// Renders widgets next to each other.
-pub struct Columns<'a, T, U>(pub(crate) Vec<Collected<'a, T, U>>);
+pub struct Columns<'a, T, U>(
+    pub(crate) Vec<Collected<'a, T, U>>,
+    pub(crate) FocusState<usize>
+);
 
 impl<'a, T, U> Columns<'a, T, U> {
     pub fn new () -> Self {
-        Self(vec![])
+        Self(vec![], FocusState::default())
     }
 }
 
@@ -15,4 +19,31 @@ impl<'a, T, U> Collection<'a, T, U> for Columns<'a, T, U> {
         self.0.push(widget.into_collected());
         self
     }
+    /// Add a column that receives input
+    fn add_mut (mut self, widget: &'a mut (impl Input<T, U> + 'a)) -> Self {
+        self.0.push(Collected::Mut(widget));
+        self
+    }
+}
+
+impl<'a, T, U> Focus<Collected<'a, T, U>> for Columns<'a, T, U> {
+    fn items (&self) -> &Vec<Collected<'a, T, U>> {
+        &self.0
+    }
+    fn items_mut (&mut self) -> &mut Vec<Collected<'a, T, U>> {
+        &mut self.0
+    }
+    fn state (&self) -> &FocusState<usize> {
+        &self.1
+    }
+    fn state_mut (&mut self) -> &mut FocusState<usize> {
+        &mut self.1
+    }
+}
+
+/// Input goes to the selected column first, then to the others; see `Context::route`.
+impl<'a, T: Context, U> Input<T, U> for Columns<'a, T, U> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        engine.route(&mut self.0, self.1.1)
+    }
 }
@@ -22,3 +22,30 @@ impl<Unit, T> Proxy<T> for Fixed<Unit, T> {
         match self { Self::X(_, w)  => w, Self::Y(_, w)  => w, Self::XY(_, w) => w }
     }
 }
+
+impl<T> Fixed<u16, T> {
+    /// The area available to the contained widget
+    pub fn inner (&self, area: [u16;4]) -> [u16;4] {
+        let [x, y, w, h] = area;
+        match self {
+            Self::X(width, _)            => [x, y, *width, h],
+            Self::Y(height, _)           => [x, y, w, *height],
+            Self::XY((width, height), _) => [x, y, *width, *height]
+        }
+    }
+    /// The size of this widget, given the size of the contained widget
+    pub fn outer (&self, size: Option<[u16;2]>) -> Option<[u16;2]> {
+        match self {
+            Self::X(width, _)            => size.map(|[_, h]|[*width, h]),
+            Self::Y(height, _)           => size.map(|[w, _]|[w, *height]),
+            Self::XY((width, height), _) => Some([*width, *height])
+        }
+    }
+}
+
+/// Input is passed to the contained widget, and its messages bubble up.
+impl<N, T, U, V: Input<T, U>> Input<T, U> for Fixed<N, V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        self.get_mut().handle(engine)
+    }
+}
@@ -0,0 +1,149 @@
+//! Divide space between the items of a container
+
+use crate::*;
+
+/// How an item of `Rows` or `Columns` is sized along the container's axis.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum Flex {
+    /// Take up the measured size of the item
+    #[default] Auto,
+    /// Take up the measured size, plus a share of the leftover space proportional to the weight
+    Grow(u16),
+    /// Take up the measured size, minus a share of the missing space proportional to the weight
+    Shrink(u16),
+    /// Take up an equal share of the leftover space
+    Fill,
+    /// Take up a percentage of the available space
+    Percent(u16),
+    /// Take up a fraction of the available space
+    Ratio(u16, u16),
+}
+
+impl Flex {
+
+    /// Divide the available space between items with the given policies and measured sizes.
+    /// Items with unknown size and `Auto` policy are treated as `Fill`.
+    pub fn divide (space: u16, items: &[(Flex, Option<u16>)]) -> Vec<u16> {
+        let items: Vec<(Flex, u16)> = items.iter().map(|(flex, size)|match (flex, size) {
+            (Flex::Auto, None) => (Flex::Fill, 0),
+            (flex, size)       => (*flex, size.unwrap_or(0))
+        }).collect();
+        let mut sizes: Vec<u16> = items.iter().map(|(flex, size)|match flex {
+            Flex::Auto | Flex::Grow(_) | Flex::Shrink(_) => *size,
+            Flex::Fill          => 0,
+            Flex::Percent(p)    => (space as u32 * *p as u32 / 100).min(space as u32) as u16,
+            Flex::Ratio(_, 0)   => 0,
+            Flex::Ratio(n, d)   => (space as u32 * *n as u32 / *d as u32).min(space as u32) as u16,
+        }).collect();
+        let total: u32 = sizes.iter().map(|size|*size as u32).sum();
+        if total <= space as u32 {
+            // Distribute leftover space between growing items
+            let weights: Vec<u16> = items.iter().map(|(flex, _)|match flex {
+                Flex::Grow(weight) => *weight,
+                Flex::Fill         => 1,
+                _                  => 0
+            }).collect();
+            let shares = Self::distribute(space - total as u16, &weights);
+            for (size, share) in sizes.iter_mut().zip(shares) {
+                *size += share
+            }
+        } else {
+            // Take missing space from shrinking items, until they run out
+            let mut missing = (total - space as u32).min(u16::MAX as u32) as u16;
+            while missing > 0 {
+                let weights: Vec<u16> = items.iter().zip(sizes.iter()).map(|((flex, _), size)|
+                    match flex { Flex::Shrink(weight) if *size > 0 => *weight, _ => 0 }
+                ).collect();
+                if weights.iter().all(|weight|*weight == 0) {
+                    break
+                }
+                for (size, share) in sizes.iter_mut().zip(Self::distribute(missing, &weights)) {
+                    let share = share.min(*size);
+                    *size -= share;
+                    missing -= share;
+                }
+            }
+        }
+        sizes
+    }
+
+    /// Split an amount proportionally to the given weights,
+    /// handing out the remainder one by one from the first item.
+    fn distribute (amount: u16, weights: &[u16]) -> Vec<u16> {
+        let total: u32 = weights.iter().map(|weight|*weight as u32).sum();
+        if total == 0 {
+            return vec![0; weights.len()]
+        }
+        let mut shares: Vec<u16> = weights.iter()
+            .map(|weight|(amount as u32 * *weight as u32 / total) as u16)
+            .collect();
+        let mut remainder = amount - shares.iter().sum::<u16>();
+        for (share, weight) in shares.iter_mut().zip(weights) {
+            if remainder == 0 {
+                break
+            }
+            if *weight > 0 {
+                *share += 1;
+                remainder -= 1;
+            }
+        }
+        shares
+    }
+
+}
+
+/// Wraps a widget, setting how it's sized in `Rows` and `Columns`.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Flexed<T>(
+    /// The sizing policy
+    pub Flex,
+    /// The sized widget
+    pub T
+);
+
+impl<T, U, V: Output<T, U>> Output<T, U> for Flexed<V> {
+    fn render (&self, engine: &mut T) -> Result<Option<U>> {
+        self.1.render(engine)
+    }
+    fn measure (&self, engine: &mut T) -> Result<Option<U>> {
+        self.1.measure(engine)
+    }
+    fn flex (&self) -> Flex {
+        self.0
+    }
+}
+
+impl<T, U, V: Input<T, U>> Input<T, U> for Flexed<V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        self.1.handle(engine)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::flex::*;
+
+    #[test]
+    fn should_divide_space () {
+        // Sidebar, main pane and status bar
+        assert_eq!(Flex::divide(24, &[
+            (Flex::Percent(25), Some(3)), (Flex::Fill, Some(1)), (Flex::Auto, Some(1))
+        ]), vec![6, 17, 1]);
+        // Leftover space is shared by weight
+        assert_eq!(Flex::divide(10, &[
+            (Flex::Grow(1), Some(0)), (Flex::Grow(3), Some(2)), (Flex::Ratio(1, 5), None)
+        ]), vec![2, 6, 2]);
+        // Missing space is taken from shrinking items only
+        assert_eq!(Flex::divide(10, &[
+            (Flex::Shrink(1), Some(8)), (Flex::Auto, Some(6))
+        ]), vec![4, 6]);
+        // Unknown sizes fill the leftover space
+        assert_eq!(Flex::divide(10, &[
+            (Flex::Auto, Some(4)), (Flex::Auto, None)
+        ]), vec![4, 6]);
+        // Shares larger than the whole are capped to it
+        assert_eq!(Flex::divide(60000, &[(Flex::Percent(150), None)]), vec![60000]);
+        assert_eq!(Flex::divide(40000, &[(Flex::Ratio(3, 2), None)]), vec![40000]);
+    }
+
+}
@@ -174,6 +174,38 @@ impl<T> Focus<T> for FocusList<T> {
 
 }
 
+/// Pass input to a list of items: to the selected one first, then to each of
+/// the others in order, until one of them returns a message, which bubbles up.
+/// Items that don't receive input (i.e. not added with `add_mut`) are skipped.
+pub fn dispatch <'a, T, U> (
+    items: &mut [Collected<'a, T, U>], selected: Option<usize>, engine: &mut T
+) -> Result<Option<U>> {
+    if let Some(item) = selected.and_then(|index|items.get_mut(index)) {
+        if let Some(message) = item.handle(engine)? {
+            return Ok(Some(message))
+        }
+    }
+    for (index, item) in items.iter_mut().enumerate() {
+        if Some(index) == selected {
+            continue
+        }
+        if let Some(message) = item.handle(engine)? {
+            return Ok(Some(message))
+        }
+    }
+    Ok(None)
+}
+
+/// Input is passed to the selected item, and its messages bubble up.
+impl<T, U, V: Input<T, U>> Input<T, U> for FocusList<V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        match Focus::get_mut(self) {
+            Some(item) => item.handle(engine),
+            None => Ok(None)
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::layouts::focus::*;
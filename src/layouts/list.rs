@@ -0,0 +1,132 @@
+//! Lists of selectable items
+
+use super::*;
+
+use std::cell::Cell;
+
+/// A `FocusList` that is drawn with its selected item highlighted,
+/// scrolling to keep the selected item in view.
+/// When the selection changes, the new index is returned from `handle` as the message.
+#[derive(Debug, Default)]
+pub struct List<T> {
+    /// The items and the selection
+    pub items:   FocusList<T>,
+    /// Index of the first visible item
+    pub offset:  Cell<usize>,
+    /// How many items were visible during the last render
+    pub visible: Cell<usize>,
+}
+
+impl<T> List<T> {
+
+    /// Create a list, selecting its first item
+    pub fn new (items: Vec<T>) -> Self {
+        let mut items = FocusList::new(items);
+        items.select(0);
+        Self { items, offset: Cell::new(0), visible: Cell::new(0) }
+    }
+
+    /// Select the first item
+    pub fn select_first (&mut self) -> bool {
+        self.select(0)
+    }
+
+    /// Select the last item
+    pub fn select_last (&mut self) -> bool {
+        self.select(self.len().saturating_sub(1))
+    }
+
+    /// Move the selection down by a page, i.e. the number of items that were last visible.
+    pub fn page_down (&mut self) -> bool {
+        let page = self.visible.get().max(1);
+        let index = self.selected().map_or(0, |index|index + page);
+        self.select(index.min(self.len().saturating_sub(1)))
+    }
+
+    /// Move the selection up by a page, i.e. the number of items that were last visible.
+    pub fn page_up (&mut self) -> bool {
+        let page = self.visible.get().max(1);
+        self.select(self.selected().map_or(0, |index|index.saturating_sub(page)))
+    }
+
+    /// Given the height of each item, scroll the least distance that brings
+    /// the selected item into a viewport of the given height.
+    /// Returns the index of the first visible item.
+    pub fn follow (&self, heights: &[u16], height: u16) -> usize {
+        let mut offset = self.offset.get().min(heights.len().saturating_sub(1));
+        if let Some(selected) = self.selected().filter(|index|*index < heights.len()) {
+            if selected < offset {
+                offset = selected
+            }
+            let span = |offset: usize|heights[offset..=selected].iter().map(|h|*h as u32).sum::<u32>();
+            while offset < selected && span(offset) > height as u32 {
+                offset += 1
+            }
+        }
+        self.offset.set(offset);
+        offset
+    }
+
+    /// Like `follow`, for items that are all one row high.
+    pub fn follow_rows (&self, height: u16) -> usize {
+        let mut offset = self.offset.get().min(self.len().saturating_sub(1));
+        if let Some(selected) = self.selected().filter(|index|*index < self.len()) {
+            offset = offset.min(selected).max((selected + 1).saturating_sub(height.max(1) as usize));
+        }
+        self.offset.set(offset);
+        offset
+    }
+
+}
+
+impl<T> Focus<T> for List<T> {
+
+    fn items (&self) -> &Vec<T> {
+        self.items.items()
+    }
+
+    fn items_mut (&mut self) -> &mut Vec<T> {
+        self.items.items_mut()
+    }
+
+    fn state (&self) -> &FocusState<usize> {
+        self.items.state()
+    }
+
+    fn state_mut (&mut self) -> &mut FocusState<usize> {
+        self.items.state_mut()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_keep_selection_in_view () {
+        let mut list = List::new(vec!["a", "b", "c", "d", "e", "f"]);
+        let heights = [1, 1, 2, 1, 1, 1];
+        assert_eq!(list.follow(&heights, 3), 0);
+        list.select(2);
+        assert_eq!(list.follow(&heights, 3), 1);
+        list.visible.set(3);
+        assert!(list.page_down());
+        assert_eq!(list.selected(), Some(5));
+        assert_eq!(list.follow(&heights, 3), 3);
+        list.page_up();
+        list.page_up();
+        assert_eq!(list.selected(), Some(0));
+        assert_eq!(list.follow(&heights, 3), 0);
+        // One row per item
+        assert_eq!(list.follow_rows(2), 0);
+        list.select(4);
+        assert_eq!(list.follow_rows(2), 3);
+        list.select(1);
+        assert_eq!(list.follow_rows(2), 1);
+        list.select_last();
+        assert!(list.select_next());
+        assert_eq!(list.selected(), Some(0));
+    }
+
+}
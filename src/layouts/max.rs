@@ -19,3 +19,30 @@ impl<Unit, T> Proxy<T> for Max<Unit, T> {
         match self { Self::X(_, w)  => w, Self::Y(_, w)  => w, Self::XY(_, w) => w }
     }
 }
+
+impl<T> Max<u16, T> {
+    /// The area available to the contained widget
+    pub fn inner (&self, area: [u16;4]) -> [u16;4] {
+        let [x, y, w, h] = area;
+        match self {
+            Self::X(max_w, _)          => [x, y, w.min(*max_w), h],
+            Self::Y(max_h, _)          => [x, y, w, h.min(*max_h)],
+            Self::XY((max_w, max_h), _) => [x, y, w.min(*max_w), h.min(*max_h)]
+        }
+    }
+    /// The size of this widget, given the size of the contained widget
+    pub fn outer (&self, size: Option<[u16;2]>) -> Option<[u16;2]> {
+        size.map(|[w, h]|match self {
+            Self::X(max_w, _)          => [w.min(*max_w), h],
+            Self::Y(max_h, _)          => [w, h.min(*max_h)],
+            Self::XY((max_w, max_h), _) => [w.min(*max_w), h.min(*max_h)]
+        })
+    }
+}
+
+/// Input is passed to the contained widget, and its messages bubble up.
+impl<N, T, U, V: Input<T, U>> Input<T, U> for Max<N, V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        self.get_mut().handle(engine)
+    }
+}
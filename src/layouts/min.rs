@@ -19,3 +19,26 @@ impl<Unit, T> Proxy<T> for Min<Unit, T> {
         match self { Self::X(_, w)  => w, Self::Y(_, w)  => w, Self::XY(_, w) => w }
     }
 }
+
+impl<T> Min<u16, T> {
+    /// The area available to the contained widget. This is never larger than
+    /// the parent's area, so the minimum only applies to the reported size.
+    pub fn inner (&self, area: [u16;4]) -> [u16;4] {
+        area
+    }
+    /// The size of this widget, given the size of the contained widget
+    pub fn outer (&self, size: Option<[u16;2]>) -> Option<[u16;2]> {
+        size.map(|[w, h]|match self {
+            Self::X(min_w, _)          => [w.max(*min_w), h],
+            Self::Y(min_h, _)          => [w, h.max(*min_h)],
+            Self::XY((min_w, min_h), _) => [w.max(*min_w), h.max(*min_h)]
+        })
+    }
+}
+
+/// Input is passed to the contained widget, and its messages bubble up.
+impl<N, T, U, V: Input<T, U>> Input<T, U> for Min<N, V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        self.get_mut().handle(engine)
+    }
+}
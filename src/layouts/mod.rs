@@ -4,16 +4,24 @@ mod align; pub use align::*;
 mod columns; pub use columns::*;
 mod files; pub use files::*;
 mod fixed; pub use fixed::*;
+mod flex; pub use flex::*;
 mod focus; pub use focus::*;
 mod layers; pub use layers::*;
+mod list; pub use list::*;
 mod max; pub use max::*;
 mod min; pub use min::*;
 mod offset; pub use offset::*;
 mod rows; pub use rows::*;
+mod scroll; pub use scroll::*;
+mod stacked; pub use stacked::*;
 mod style; pub use style::*;
+mod table; pub use table::*;
 mod text; pub use text::*;
+mod text_area; pub use text_area::*;
+mod text_input; pub use text_input::*;
 mod tabs; pub use tabs::*;
 mod toggle; pub use toggle::*;
+mod tree; pub use tree::*;
 
 use std::{fmt::{Debug}};
 
@@ -147,4 +155,33 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn should_dispatch_to_focused_first () -> Result<()> {
+
+        struct Button(usize, bool);
+
+        impl Input<(), usize> for Button {
+            fn handle (&mut self, _: &mut ()) -> Result<Option<usize>> {
+                Ok(if self.1 { Some(self.0) } else { None })
+            }
+        }
+
+        let (mut a, mut b, mut c) = (Button(0, false), Button(1, true), Button(2, true));
+        let mut label = "label";
+
+        // Without focus, the first item that returns a message wins
+        let mut rows = Rows::new().add_mut(&mut label).add_mut(&mut a).add_mut(&mut b).add_mut(&mut c);
+        assert_eq!(rows.handle(&mut ())?, Some(1));
+
+        // The focused item gets the first chance to handle input
+        rows.select(3);
+        assert_eq!(rows.handle(&mut ())?, Some(2));
+
+        // Messages bubble up through nested containers
+        let mut columns = Columns::new().add_mut(&mut rows);
+        assert_eq!(columns.handle(&mut ())?, Some(2));
+
+        Ok(())
+    }
+
 }
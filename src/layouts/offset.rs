@@ -19,3 +19,22 @@ impl<Unit, T> Proxy<T> for Offset<Unit, T> {
         &mut self.2
     }
 }
+
+impl<T> Offset<u16, T> {
+    /// The area available to the contained widget
+    pub fn inner (&self, area: [u16;4]) -> [u16;4] {
+        let [x, y, w, h] = area;
+        [x + self.0, y + self.1, w.saturating_sub(self.0), h.saturating_sub(self.1)]
+    }
+    /// The size of this widget, given the size of the contained widget
+    pub fn outer (&self, size: Option<[u16;2]>) -> Option<[u16;2]> {
+        size.map(|[w, h]|[w + self.0, h + self.1])
+    }
+}
+
+/// Input is passed to the contained widget, and its messages bubble up.
+impl<N, T, U, V: Input<T, U>> Input<T, U> for Offset<N, V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        self.get_mut().handle(engine)
+    }
+}
@@ -1 +1,142 @@
 //! Scrollable viewports
+
+use crate::*;
+
+use std::cell::Cell;
+
+/// Renders a widget that may be larger than the available area
+/// through a viewport, which can be scrolled on both axes.
+///
+/// If the contained widget asks for an area to be kept in view
+/// (e.g. the selected item of a `FocusList`), the viewport follows it
+/// whenever it changes.
+#[derive(Debug, Default)]
+pub struct Scrollable<T> {
+    /// The scrolled widget
+    pub content:    T,
+    /// Whether to draw scrollbars when the content overflows
+    pub scrollbars: bool,
+    /// Distance from the top left corner of the content to that of the viewport
+    pub offset:     Cell<[u16;2]>,
+    /// Size of the content during the last render
+    pub size:       Cell<[u16;2]>,
+    /// Size of the viewport during the last render
+    pub viewport:   Cell<[u16;2]>,
+    /// The area that was last asked to be kept in view, relative to the content
+    pub revealed:   Cell<Option<[u16;4]>>,
+}
+
+impl<T> Scrollable<T> {
+
+    /// Wrap a widget in a scrollable viewport, with scrollbars.
+    pub fn new (content: T) -> Self {
+        Self {
+            content,
+            scrollbars: true,
+            offset:     Cell::new([0, 0]),
+            size:       Cell::new([0, 0]),
+            viewport:   Cell::new([0, 0]),
+            revealed:   Cell::new(None),
+        }
+    }
+
+    /// Set whether to draw scrollbars.
+    pub fn scrollbars (mut self, scrollbars: bool) -> Self {
+        self.scrollbars = scrollbars;
+        self
+    }
+
+    /// The largest possible offset on each axis
+    pub fn max_offset (&self) -> [u16;2] {
+        let [cw, ch] = self.size.get();
+        let [vw, vh] = self.viewport.get();
+        [cw.saturating_sub(vw), ch.saturating_sub(vh)]
+    }
+
+    /// Update the content and viewport sizes, keeping the offset within bounds.
+    pub fn fit (&self, size: [u16;2], viewport: [u16;2]) {
+        self.size.set(size);
+        self.viewport.set(viewport);
+        self.scroll_to(self.offset.get());
+    }
+
+    /// Scroll to an absolute offset, within bounds. Returns whether the offset changed.
+    pub fn scroll_to (&self, [x, y]: [u16;2]) -> bool {
+        let [max_x, max_y] = self.max_offset();
+        let offset = [x.min(max_x), y.min(max_y)];
+        offset != self.offset.replace(offset)
+    }
+
+    /// Scroll by a relative distance, within bounds. Returns whether the offset changed.
+    pub fn scroll_by (&self, dx: i32, dy: i32) -> bool {
+        let [x, y] = self.offset.get();
+        let clamp = |value: u16, delta: i32|(value as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+        self.scroll_to([clamp(x, dx), clamp(y, dy)])
+    }
+
+    /// Scroll by a number of pages, i.e. viewport heights.
+    pub fn scroll_pages (&self, pages: i32) -> bool {
+        self.scroll_by(0, pages * self.viewport.get()[1].max(1) as i32)
+    }
+
+    /// Scroll the least distance that brings an area of the content into view,
+    /// if it's different from the one that was last revealed.
+    /// This way, the user can still scroll away from it.
+    pub fn reveal (&self, area: [u16;4]) -> bool {
+        if self.revealed.replace(Some(area)) == Some(area) {
+            return false
+        }
+        let [x, y, w, h] = area;
+        let [vw, vh] = self.viewport.get();
+        let [mut ox, mut oy] = self.offset.get();
+        let follow = |offset: &mut u16, start: u16, length: u16, view: u16| {
+            if start < *offset {
+                *offset = start
+            } else if start.saturating_add(length) > offset.saturating_add(view) {
+                *offset = start.saturating_add(length).saturating_sub(view).min(start)
+            }
+        };
+        follow(&mut ox, x, w, vw);
+        follow(&mut oy, y, h, vh);
+        self.scroll_to([ox, oy])
+    }
+
+}
+
+impl<T> Proxy<T> for Scrollable<T> {
+    fn get (&self) -> &T {
+        &self.content
+    }
+    fn get_mut (&mut self) -> &mut T {
+        &mut self.content
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_scroll_within_bounds () {
+        let scroll = Scrollable::new(());
+        scroll.fit([10, 100], [10, 20]);
+        assert!(scroll.scroll_pages(1));
+        assert_eq!(scroll.offset.get(), [0, 20]);
+        assert!(scroll.scroll_by(5, 100));
+        assert_eq!(scroll.offset.get(), [0, 80]);
+        assert!(!scroll.scroll_by(0, 1));
+        // Keep a newly revealed area in view
+        assert!(scroll.reveal([0, 10, 10, 1]));
+        assert_eq!(scroll.offset.get(), [0, 10]);
+        assert!(scroll.reveal([0, 40, 10, 2]));
+        assert_eq!(scroll.offset.get(), [0, 22]);
+        // Scroll away from it
+        assert!(scroll.scroll_to([0, 0]));
+        assert!(!scroll.reveal([0, 40, 10, 2]));
+        // Shrinking the content clamps the offset
+        scroll.scroll_to([0, 50]);
+        scroll.fit([10, 30], [10, 20]);
+        assert_eq!(scroll.offset.get(), [0, 10]);
+    }
+
+}
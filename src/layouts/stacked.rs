@@ -1,3 +1,5 @@
+use crate::*;
+use super::*;
 
 /// Order multiple `Widget`s along X (columns), Y (rows), or Z (layers).
 pub struct Stacked<'a, T, U>(
@@ -9,7 +11,7 @@ pub struct Stacked<'a, T, U>(
 
 impl<'a, T, U> std::fmt::Debug for Stacked<'a, T, U> {
     fn fmt (&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Stacked({:?}, {:?})", self.0, &self.1)
+        write!(f, "Stacked({:?}, {:?})", self.0, self.1)
     }
 }
 
@@ -32,30 +34,12 @@ impl<'a, T, U> Stacked<'a, T, U> {
 
 }
 
-    struct StackedWidget1;
-
-    impl<T, U> Output<T, U> for StackedWidget1 {
-        fn render (&self, engine: &mut T) -> Result<Option<U>> {
-            Stacked::x(|add|{
-                add("String");
-                add(String::from("String"));
-                add(Stacked::y(|add|{
-                    add("String");
-                    add(String::from("String"));
-                    add(Stacked::z(|add|{
-                        add("String");
-                        add(String::from("String"));
-                    }));
-                }));
-            }).render(engine)
-        }
-    }
-
-    #[test]
-    fn should_stack_callback () -> Result<()> {
-        StackedWidget1.render(&mut ())?;
-        Ok(())
+/// Input is passed to each of the stacked components in order; see `dispatch`.
+impl<'a, T, U> Input<T, U> for Stacked<'a, T, U> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        dispatch(&mut self.1, None, engine)
     }
+}
 
 /// Like `Stacked`, but keeps track of focus
 #[derive(Debug)]
@@ -94,12 +78,44 @@ impl<'a, T, U> Focus<Collected<'a, T, U>> for FocusStack<'a, T, U> {
     }
 }
 
-//impl<'a> Widget for FocusStack<'a> {
-    //impl_render!(self, out, area => {
-        //if let Some(item) = self.get() {
-            //item.render(out, area)
-        //} else {
-            //Ok((0, 0))
-        //}
-    //});
-//}
+/// Input is passed to the focused component first; see `dispatch`.
+impl<'a, T, U> Input<T, U> for FocusStack<'a, T, U> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        dispatch(&mut self.0.1, self.1.1, engine)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{*, layouts::*};
+
+    #[test]
+    fn should_dispatch_to_focused_stack_item_first () -> Result<()> {
+
+        struct Button(usize);
+
+        impl Input<(), usize> for Button {
+            fn handle (&mut self, _: &mut ()) -> Result<Option<usize>> {
+                Ok(Some(self.0))
+            }
+        }
+
+        let (mut a, mut b, mut c) = (Button(0), Button(1), Button(2));
+        let mut stack = FocusStack::new(Stacked(Axis::Y, vec![
+            Collected::Mut(&mut a), Collected::Mut(&mut b), Collected::Mut(&mut c),
+        ]));
+
+        // Without focus, the first item gets the event
+        assert_eq!(stack.handle(&mut ())?, Some(0));
+
+        // The focused item gets the event first
+        stack.select(2);
+        assert_eq!(stack.handle(&mut ())?, Some(2));
+
+        // A plain stack passes it to its items in order
+        assert_eq!(stack.0.handle(&mut ())?, Some(0));
+
+        Ok(())
+    }
+
+}
@@ -1,7 +1,119 @@
-pub type Style<T: Stylable> = fn(T)->T;
+//! Styled text
 
+use crate::*;
+use super::*;
+
+use crossterm::style::Color;
+
+/// Colors and attributes of text.
+/// When styles are nested, the inner one is applied on top of the outer one.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Style {
+    pub fg:        Option<Color>,
+    pub bg:        Option<Color>,
+    pub bold:      bool,
+    pub italic:    bool,
+    pub underline: bool,
+    pub reverse:   bool,
+    pub dim:       bool,
+}
+
+impl Style {
+    /// The default style
+    pub fn new () -> Self {
+        Self::default()
+    }
+    /// Set the foreground color
+    pub fn fg (self, fg: Color) -> Self {
+        Self { fg: Some(fg), ..self }
+    }
+    /// Set the background color
+    pub fn bg (self, bg: Color) -> Self {
+        Self { bg: Some(bg), ..self }
+    }
+    pub fn bold (self) -> Self {
+        Self { bold: true, ..self }
+    }
+    pub fn italic (self) -> Self {
+        Self { italic: true, ..self }
+    }
+    pub fn underline (self) -> Self {
+        Self { underline: true, ..self }
+    }
+    pub fn reverse (self) -> Self {
+        Self { reverse: true, ..self }
+    }
+    pub fn dim (self) -> Self {
+        Self { dim: true, ..self }
+    }
+    /// Apply another style on top of this one
+    pub fn patch (self, other: Self) -> Self {
+        Self {
+            fg:        other.fg.or(self.fg),
+            bg:        other.bg.or(self.bg),
+            bold:      self.bold      || other.bold,
+            italic:    self.italic    || other.italic,
+            underline: self.underline || other.underline,
+            reverse:   self.reverse   || other.reverse,
+            dim:       self.dim       || other.dim,
+        }
+    }
+}
+
+/// Text widgets, to which a style can be attached.
 pub trait Stylable {}
 
+impl Stylable for &str {}
+
+impl Stylable for String {}
+
+impl<S: AsRef<str>> Stylable for Paragraph<S> {}
+
+/// Attach a style to a widget, e.g. `"foo".style(Style::new().bold())`.
 pub trait AddStyle<T> {
-    fn style (self, style: Style<Self>) -> T;
+    fn style (self, style: Style) -> T;
+}
+
+impl<T: Stylable> AddStyle<Styled<T>> for T {
+    fn style (self, style: Style) -> Styled<T> {
+        Styled(style, self)
+    }
+}
+
+/// A widget drawn with a style. Engines that can't display styles render it as is.
+#[derive(Clone, Default, Debug)]
+pub struct Styled<T>(
+    /// The style
+    pub Style,
+    /// The styled widget
+    pub T
+);
+
+impl<T> Stylable for Styled<T> {}
+
+/// A styled piece of a line of text.
+pub type Span<T> = Styled<T>;
+
+/// A line of text made of differently styled spans.
+#[derive(Clone, Default, Debug)]
+pub struct Line<T>(pub Vec<Span<T>>);
+
+impl<T> Stylable for Line<T> {}
+
+impl<T> Line<T> {
+    /// Create an empty line
+    pub fn new () -> Self {
+        Self(vec![])
+    }
+    /// Add a span to the line
+    pub fn span (mut self, text: T, style: Style) -> Self {
+        self.0.push(Styled(style, text));
+        self
+    }
+}
+
+impl<T, U, V: Input<T, U>> Input<T, U> for Styled<V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        self.1.handle(engine)
+    }
 }
@@ -0,0 +1,198 @@
+//! Tables of text
+
+use super::*;
+
+use std::cmp::Ordering;
+use std::cell::RefCell;
+
+use unicode_width::UnicodeWidthStr;
+
+/// How wide a column of a `Table` is.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// As wide as its widest cell, shrinking if the columns don't fit
+    #[default] Auto,
+    /// A number of columns
+    Fixed(u16),
+    /// A percentage of the table's width
+    Percent(u16),
+}
+
+/// A column of a `Table`.
+#[derive(Clone, Default, Debug)]
+pub struct TableColumn {
+    /// Shown in the header row
+    pub title: String,
+    /// How wide the column is
+    pub width: ColumnWidth,
+}
+
+impl TableColumn {
+    /// Create an auto-sized column
+    pub fn new (title: impl Into<String>) -> Self {
+        Self { title: title.into(), width: ColumnWidth::Auto }
+    }
+    /// Set the width of the column
+    pub fn width (mut self, width: ColumnWidth) -> Self {
+        self.width = width;
+        self
+    }
+}
+
+/// Rows of text cells under a header row, with a selected row and an optional sort column.
+/// Cells that don't fit in their column are truncated with an ellipsis.
+#[derive(Debug, Default)]
+pub struct Table {
+    /// The columns
+    pub columns: Vec<TableColumn>,
+    /// The rows of cells, and the selected row
+    pub rows:    List<Vec<String>>,
+    /// The column by which the rows are sorted, and whether in ascending order
+    pub sort:    Option<(usize, bool)>,
+    /// Widths of the columns during the last render
+    pub widths:  RefCell<Vec<u16>>,
+}
+
+/// Number of blank columns between two columns of a table.
+pub const TABLE_GAP: u16 = 1;
+
+impl Table {
+
+    /// Create a table, selecting its first row
+    pub fn new (columns: Vec<TableColumn>, rows: Vec<Vec<String>>) -> Self {
+        Self { columns, rows: List::new(rows), sort: None, widths: RefCell::new(vec![]) }
+    }
+
+    /// The title of a column, followed by an arrow if the rows are sorted by it
+    pub fn title (&self, column: usize) -> String {
+        let title = self.columns.get(column).map_or("", |column|column.title.as_str());
+        match self.sort {
+            Some((sorted, true))  if sorted == column => format!("{title} ▲"),
+            Some((sorted, false)) if sorted == column => format!("{title} ▼"),
+            _ => title.into()
+        }
+    }
+
+    /// Divide a width between the columns
+    pub fn layout (&self, width: u16) -> Vec<u16> {
+        let gaps = TABLE_GAP.saturating_mul(u16::try_from(self.columns.len()).unwrap_or(u16::MAX).saturating_sub(1));
+        let items: Vec<(Flex, Option<u16>)> = self.columns.iter().enumerate().map(|(index, column)|
+            match column.width {
+                ColumnWidth::Fixed(width)     => (Flex::Auto, Some(width)),
+                ColumnWidth::Percent(percent) => (Flex::Percent(percent), None),
+                ColumnWidth::Auto             => (Flex::Shrink(1), Some(self.content_width(index))),
+            }
+        ).collect();
+        let widths = Flex::divide(width.saturating_sub(gaps), &items);
+        *self.widths.borrow_mut() = widths.clone();
+        widths
+    }
+
+    /// The column at a horizontal position, according to the last layout
+    pub fn column_at (&self, x: u16) -> Option<usize> {
+        let mut start = 0;
+        for (index, width) in self.widths.borrow().iter().enumerate() {
+            if x >= start && x < start + width {
+                return Some(index)
+            }
+            start += width + TABLE_GAP;
+        }
+        None
+    }
+
+    /// Sort the rows by a column, keeping the same row selected.
+    /// If they're already sorted by it, reverse the order.
+    pub fn sort_by (&mut self, column: usize) {
+        if column >= self.columns.len() {
+            return
+        }
+        let ascending = match self.sort {
+            Some((sorted, ascending)) if sorted == column => !ascending,
+            _ => true
+        };
+        self.sort = Some((column, ascending));
+        let selected = self.rows.selected();
+        let mut rows: Vec<(usize, Vec<String>)> = std::mem::take(self.rows.items_mut())
+            .into_iter().enumerate().collect();
+        rows.sort_by(|(_, a), (_, b)| {
+            let order = compare(a.get(column), b.get(column));
+            if ascending { order } else { order.reverse() }
+        });
+        let selected = rows.iter().position(|(index, _)|Some(*index) == selected);
+        *self.rows.items_mut() = rows.into_iter().map(|(_, row)|row).collect();
+        if let Some(selected) = selected {
+            self.rows.select(selected);
+        }
+    }
+
+    /// The width of the widest cell of a column, including its title
+    fn content_width (&self, column: usize) -> u16 {
+        self.rows.iter()
+            .map(|row|row.get(column).map_or(0, |cell|cell.width()))
+            .chain([self.title(column).width()])
+            .max().map_or(0, |width|u16::try_from(width).unwrap_or(u16::MAX))
+    }
+
+}
+
+/// Compare cells as numbers if they both are, and as text otherwise.
+fn compare (a: Option<&String>, b: Option<&String>) -> Ordering {
+    let a = a.map_or("", |a|a.as_str());
+    let b = b.map_or("", |b|b.as_str());
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b)
+    }
+}
+
+impl Focus<Vec<String>> for Table {
+
+    fn items (&self) -> &Vec<Vec<String>> {
+        self.rows.items()
+    }
+
+    fn items_mut (&mut self) -> &mut Vec<Vec<String>> {
+        self.rows.items_mut()
+    }
+
+    fn state (&self) -> &FocusState<usize> {
+        self.rows.state()
+    }
+
+    fn state_mut (&mut self) -> &mut FocusState<usize> {
+        self.rows.state_mut()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_size_and_sort_columns () {
+        let rows = vec![
+            vec!["init".into(), "1".into(),  "0.1".into()],
+            vec!["sshd".into(), "12".into(), "0.0".into()],
+            vec!["cargo".into(), "9".into(), "97.5".into()],
+        ];
+        let mut table = Table::new(vec![
+            TableColumn::new("Name"),
+            TableColumn::new("PID").width(ColumnWidth::Fixed(5)),
+            TableColumn::new("CPU").width(ColumnWidth::Percent(20)),
+        ], rows);
+        assert_eq!(table.layout(30), vec![5, 5, 5]);
+        assert_eq!(table.layout(12), vec![3, 5, 2]);
+        assert_eq!(table.column_at(6), Some(1));
+        table.select(2);
+        table.sort_by(1);
+        let pids: Vec<&str> = table.iter().map(|row|row[1].as_str()).collect();
+        assert_eq!(pids, ["1", "9", "12"]);
+        assert_eq!(table.selected(), Some(1));
+        table.sort_by(1);
+        assert_eq!(table.title(1), "PID ▼");
+        assert_eq!(table.get().map(|row|row[0].as_str()), Some("cargo"));
+        assert_eq!(table.iter().next().map(|row|row[1].as_str()), Some("12"));
+    }
+
+}
@@ -50,10 +50,11 @@ impl<T> Tabbed<T> {
         Self::new(Some(TabSide::Bottom), pages)
     }
 
-    /// Create a container holding the tab container and the active tab
-    pub fn layout <'a, U, V> (&'a self) -> Collected<'a, U, V> where
+    /// Create a container holding the tab container and the active tab.
+    /// The label of the focused tab is drawn with the `highlight` style.
+    pub fn layout <'a, U, V> (&'a self, highlight: Style) -> Collected<'a, U, V> where
         T:                 Output<U, V>,
-        String:            Output<U, V>,
+        Styled<String>:    Output<U, V>,
         Columns<'a, U, V>: Output<U, V>,
         Rows<'a, U, V>:    Output<U, V>,
         u16:               Output<U, V>,
@@ -65,7 +66,7 @@ impl<T> Tabbed<T> {
                 None       => return Collected::None
             }
             Some(side) => {
-                let tabs = self.layout_tabs();
+                let tabs = self.layout_tabs(highlight);
                 let space = if page.is_some() { 1u16 } else { 0u16 };
                 match side {
                     TabSide::Left => {
@@ -86,8 +87,8 @@ impl<T> Tabbed<T> {
     }
 
     /// Create a container holding the tabs
-    pub fn layout_tabs <'a, U: 'a, V: 'a> (&'a self) -> Option<Box<dyn Output<U, V> + 'a>> where
-        String:            Output<U, V>,
+    pub fn layout_tabs <'a, U: 'a, V: 'a> (&'a self, highlight: Style) -> Option<Box<dyn Output<U, V> + 'a>> where
+        Styled<String>:    Output<U, V>,
         Columns<'a, U, V>: Output<U, V> + Collection<'a, U, V>,
         Rows<'a, U, V>:    Output<U, V> + Collection<'a, U, V>
     {
@@ -95,27 +96,22 @@ impl<T> Tabbed<T> {
             None => None,
             Some(side) => Some(match side {
                 TabSide::Left | TabSide::Right  =>
-                    Box::new(self.layout_tabs_in(Columns::new())),
+                    Box::new(self.layout_tabs_in(Columns::new(), highlight)),
                 TabSide::Top  | TabSide::Bottom =>
-                    Box::new(self.layout_tabs_in(Rows::new()))
+                    Box::new(self.layout_tabs_in(Rows::new(), highlight))
             })
         }
     }
 
-    /// Add the tabs to the passed container
+    /// Add the tabs to the passed container, highlighting the focused one
     pub fn layout_tabs_in <'a, U, V, W: Output<U, V> + Collection<'a, U, V>> (
-        &'a self, mut container: W
-    ) -> W where String: Output<U, V> {
-        let selected = self.focus;
+        &'a self, mut container: W, highlight: Style
+    ) -> W where Styled<String>: Output<U, V> {
         let (skip, size) = self.range.unwrap_or((0, usize::MAX));
         for (index, (label, _)) in self.pages.iter().enumerate().skip(skip) {
             let label = label.clone();
             let focused = Some(index) == self.focus;
-            container = container.add(label); /*.style(&|s: String|if focused {
-                s.with(Color::Yellow).bold()
-            } else {
-                s.with(Color::White)
-            }));*/
+            container = container.add(label.style(if focused { highlight } else { Style::new() }));
             if index >= size {
                 break
             }
@@ -124,3 +120,21 @@ impl<T> Tabbed<T> {
     }
 
 }
+
+/// Input is passed to the active tab, and its messages bubble up.
+/// The tab is routed to at the same position as in `layout`, so that clicks reach it.
+impl<T: Context, U, V: Input<T, U>> Input<T, U> for Tabbed<V> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        let Some((_, page)) = self.focus.and_then(|focus|self.pages.get_mut(focus)) else {
+            return Ok(None)
+        };
+        match self.side {
+            None =>
+                page.handle(engine),
+            Some(TabSide::Left | TabSide::Top) =>
+                engine.route(&mut [Collected::None, Collected::None, Collected::Mut(page)], Some(2)),
+            Some(TabSide::Right | TabSide::Bottom) =>
+                engine.route(&mut [Collected::Mut(page), Collected::None, Collected::None], Some(0)),
+        }
+    }
+}
@@ -2,8 +2,190 @@
 
 use crate::*;
 
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 impl<S: AsRef<str>, T, U> Input<T, U> for S {
     fn handle (&mut self, _engine: &mut T) -> Result<Option<U>> {
         Ok(None)
     }
 }
+
+/// How the lines of a `Paragraph` are aligned.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default] Left,
+    Center,
+    Right,
+    /// Stretch the spaces between words so that each line fills the width,
+    /// except for the last line before a newline.
+    Justify,
+}
+
+/// A block of text that is laid out in the available width.
+#[derive(Clone, Default, Debug)]
+pub struct Paragraph<S> {
+    /// The text. Explicit newlines are preserved.
+    pub text:  S,
+    /// How lines are aligned
+    pub align: TextAlign,
+    /// Whether to wrap long lines at word boundaries.
+    /// If not, they are truncated with an ellipsis.
+    pub wrap:  bool,
+}
+
+/// A laid out line of text: its horizontal offset, and its content.
+pub type TextLine = (u16, String);
+
+impl<S: AsRef<str>> Paragraph<S> {
+
+    /// Create a left-aligned, word-wrapped paragraph
+    pub fn new (text: S) -> Self {
+        Self { text, align: TextAlign::Left, wrap: true }
+    }
+
+    /// Set the alignment
+    pub fn align (mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Truncate long lines with an ellipsis instead of wrapping them
+    pub fn truncate (mut self) -> Self {
+        self.wrap = false;
+        self
+    }
+
+    /// Lay out the text in an area of the given size. If there are more lines
+    /// than fit in `height`, the last visible one ends with an ellipsis.
+    pub fn lines (&self, width: u16, height: u16) -> Vec<TextLine> {
+        let mut lines: Vec<(String, bool)> = vec![];
+        for line in self.text.as_ref().split('\n') {
+            let line = line.trim_end_matches('\r');
+            if self.wrap {
+                let wrapped = wrap(line, width);
+                let last = wrapped.len().saturating_sub(1);
+                lines.extend(wrapped.into_iter().enumerate().map(|(index, line)|(line, index == last)));
+            } else {
+                lines.push((ellipsize(line, width), true));
+            }
+        }
+        if lines.len() > height as usize {
+            lines.truncate(height as usize);
+            if let Some((line, last)) = lines.last_mut() {
+                *line = ellipsize(&format!("{line}…"), width);
+                *last = true;
+            }
+        }
+        lines.into_iter().map(|(line, last)|self.place(line, width, last)).collect()
+    }
+
+    /// The size of the laid out text
+    pub fn size (&self, width: u16, height: u16) -> [u16;2] {
+        let lines = self.lines(width, height);
+        let w = lines.iter().map(|(x, line)|x + line.width() as u16).max().unwrap_or(0);
+        [w, lines.len() as u16]
+    }
+
+    /// Align a line within the width.
+    fn place (&self, line: String, width: u16, last: bool) -> TextLine {
+        let free = width.saturating_sub(line.width() as u16);
+        match self.align {
+            TextAlign::Left    => (0, line),
+            TextAlign::Center  => (free / 2, line),
+            TextAlign::Right   => (free, line),
+            TextAlign::Justify if last => (0, line),
+            TextAlign::Justify => (0, justify(&line, free)),
+        }
+    }
+
+}
+
+/// Break a line of text into lines no wider than `width`, at spaces if possible.
+pub fn wrap (text: &str, width: u16) -> Vec<String> {
+    let width = width.max(1) as usize;
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let space = if line.is_empty() { 0 } else { 1 };
+        if line.width() + space + word.width() <= width {
+            if space > 0 {
+                line.push(' ');
+            }
+            line.push_str(word);
+            continue
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        // Break words that are too long to fit on a line
+        for grapheme in word.graphemes(true) {
+            if line.width() + grapheme.width() > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push_str(grapheme);
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// Cut a line of text to fit in `width`, ending it with an ellipsis if it doesn't.
+pub fn ellipsize (text: &str, width: u16) -> String {
+    let width = width as usize;
+    if text.width() <= width {
+        return text.into()
+    }
+    let mut line = String::new();
+    for grapheme in text.graphemes(true) {
+        if line.width() + grapheme.width() + 1 > width {
+            break
+        }
+        line.push_str(grapheme);
+    }
+    if width > 0 {
+        line.push('…');
+    }
+    line
+}
+
+/// Widen the spaces between the words of a line by a total of `extra` columns.
+fn justify (line: &str, extra: u16) -> String {
+    let words: Vec<&str> = line.split(' ').collect();
+    let gaps = words.len().saturating_sub(1);
+    if gaps == 0 {
+        return line.into()
+    }
+    let mut justified = String::new();
+    for (index, word) in words.iter().enumerate() {
+        justified.push_str(word);
+        if index < gaps {
+            let spaces = 1 + extra as usize / gaps + if index < extra as usize % gaps { 1 } else { 0 };
+            justified.push_str(&" ".repeat(spaces));
+        }
+    }
+    justified
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_lay_out_paragraphs () {
+        let text = "the quick brown fox\njumps over the lazy dog";
+        assert_eq!(Paragraph::new(text).lines(10, 10), vec![
+            (0, "the quick".into()), (0, "brown fox".into()),
+            (0, "jumps over".into()), (0, "the lazy".into()), (0, "dog".into())
+        ]);
+        assert_eq!(Paragraph::new(text).align(TextAlign::Right).lines(10, 2), vec![
+            (1, "the quick".into()), (0, "brown fox…".into())
+        ]);
+        assert_eq!(Paragraph::new(text).align(TextAlign::Justify).lines(12, 10), vec![
+            (0, "the    quick".into()), (0, "brown fox".into()),
+            (0, "jumps   over".into()), (0, "the lazy dog".into())
+        ]);
+        assert_eq!(Paragraph::new(text).truncate().size(8, 10), [8, 2]);
+    }
+
+}
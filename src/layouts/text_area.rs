@@ -0,0 +1,413 @@
+//! Editable multi-line text
+
+use std::{cell::Cell, ops::Range};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// How many edits can be undone.
+const UNDO_LIMIT: usize = 100;
+
+/// A kind of edit. Consecutive edits of the same kind are undone together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+/// A multi-line text editor with word wrap, selection, undo and redo, and vertical scrolling.
+/// Changes are returned from `handle` as `TextInputMessage::Change`.
+///
+/// Positions are byte offsets into the value, and always lie on grapheme boundaries.
+/// Lines are wrapped to the width of the last render, which vertical movement follows.
+#[derive(Clone, Debug, Default)]
+pub struct TextArea {
+    /// The text being edited
+    pub value:  String,
+    /// Position of the cursor
+    pub cursor: usize,
+    /// The other end of the selection, if there is one
+    pub anchor: Option<usize>,
+    /// The first visible row
+    pub scroll: Cell<u16>,
+    /// Size of the area during the last render
+    pub size:   Cell<[u16;2]>,
+    /// The column that vertical movement tries to keep
+    column:     Option<u16>,
+    /// Previous values and cursor positions, oldest first
+    undo:       Vec<(String, usize)>,
+    /// Undone values and cursor positions, most recently undone last
+    redo:       Vec<(String, usize)>,
+    /// The kind of the last edit
+    last_edit:  Option<EditKind>,
+}
+
+impl TextArea {
+
+    /// Create an empty text area
+    pub fn new () -> Self {
+        Self::default()
+    }
+
+    /// Set the initial text, placing the cursor at its start
+    pub fn text (mut self, text: impl Into<String>) -> Self {
+        self.value = text.into();
+        self
+    }
+
+    /// The selected range of the value, if it's not empty
+    pub fn selection (&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let range = anchor.min(self.cursor)..anchor.max(self.cursor);
+        if range.is_empty() { None } else { Some(range) }
+    }
+
+    /// The selected text
+    pub fn selected (&self) -> &str {
+        self.selection().map_or("", |range|&self.value[range])
+    }
+
+    /// Select the whole value
+    pub fn select_all (&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.value.len();
+        self.column = None;
+        self.last_edit = None;
+    }
+
+    /// Break the value into rows that fit in the width, at spaces if possible.
+    /// Each row is a range of the value, without the line break that ends it.
+    /// A width of 0 means that lines are not wrapped.
+    pub fn rows (&self, width: u16) -> Vec<Range<usize>> {
+        let width = if width == 0 { usize::MAX } else { width as usize };
+        let mut rows = vec![];
+        let mut start = 0;
+        for line in self.value.split('\n') {
+            let end = start + line.len();
+            let mut row = start;
+            let mut column = 0;
+            // Where the row can be broken after a space, and the column there
+            let mut space: Option<(usize, usize)> = None;
+            for (index, grapheme) in line.grapheme_indices(true) {
+                let index = start + index;
+                let w = grapheme.width();
+                // Spaces may hang past the edge
+                if grapheme != " " && column + w > width && column > 0 {
+                    match space.take() {
+                        Some((after, at)) if after > row => {
+                            rows.push(row..after);
+                            row = after;
+                            column -= at;
+                        },
+                        _ => {
+                            rows.push(row..index);
+                            row = index;
+                            column = 0;
+                        }
+                    }
+                }
+                column += w;
+                if grapheme == " " {
+                    space = Some((index + 1, column));
+                }
+            }
+            rows.push(row..end);
+            start = end + 1;
+        }
+        rows
+    }
+
+    /// The row and column of a position, given the rows.
+    pub fn locate (&self, rows: &[Range<usize>], position: usize) -> [u16;2] {
+        let row = rows.iter().rposition(|row|row.start <= position).unwrap_or(0);
+        let start = rows.get(row).map_or(0, |row|row.start);
+        [self.value[start..position].width() as u16, row as u16]
+    }
+
+    /// The position nearest to a column of a row, given the rows.
+    pub fn position_at (&self, rows: &[Range<usize>], column: u16, row: u16) -> usize {
+        let row = row as usize;
+        let Some(range) = rows.get(row) else { return self.value.len() };
+        // The end of a wrapped row is the start of the next one
+        let wrapped = rows.get(row + 1).is_some_and(|next|next.start == range.end);
+        let mut x = 0;
+        let mut last = range.start;
+        for (index, grapheme) in self.value[range.clone()].grapheme_indices(true) {
+            let w = grapheme.width() as u16;
+            if x + w > column {
+                return range.start + index
+            }
+            x += w;
+            last = range.start + index;
+        }
+        if wrapped { last } else { range.end }
+    }
+
+    /// The column and row of the cursor, wrapped to the width of the last render
+    pub fn cursor_position (&self) -> [u16;2] {
+        self.locate(&self.rows(self.size.get()[0]), self.cursor)
+    }
+
+    /// Move the cursor, extending the selection if `select` is set and clearing it otherwise.
+    pub fn move_to (&mut self, position: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = position.min(self.value.len());
+        self.column = None;
+        self.last_edit = None;
+    }
+
+    /// Move the cursor one grapheme to the left. Without `select`, a selection collapses to its start.
+    pub fn left (&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.start, false),
+            _ => self.move_to(self.prev_grapheme(), select)
+        }
+    }
+
+    /// Move the cursor one grapheme to the right. Without `select`, a selection collapses to its end.
+    pub fn right (&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.end, false),
+            _ => self.move_to(self.next_grapheme(), select)
+        }
+    }
+
+    /// Move the cursor up by a number of rows, keeping its column if possible.
+    pub fn up (&mut self, rows: u16, select: bool) {
+        self.move_rows(-(rows as i32), select)
+    }
+
+    /// Move the cursor down by a number of rows, keeping its column if possible.
+    pub fn down (&mut self, rows: u16, select: bool) {
+        self.move_rows(rows as i32, select)
+    }
+
+    /// Move the cursor up by a page, i.e. the height of the last render.
+    pub fn page_up (&mut self, select: bool) {
+        self.up(self.size.get()[1].max(1), select)
+    }
+
+    /// Move the cursor down by a page, i.e. the height of the last render.
+    pub fn page_down (&mut self, select: bool) {
+        self.down(self.size.get()[1].max(1), select)
+    }
+
+    /// Move the cursor to the start of its row
+    pub fn home (&mut self, select: bool) {
+        let rows = self.rows(self.size.get()[0]);
+        let [_, row] = self.locate(&rows, self.cursor);
+        self.move_to(self.position_at(&rows, 0, row), select)
+    }
+
+    /// Move the cursor to the end of its row
+    pub fn end (&mut self, select: bool) {
+        let rows = self.rows(self.size.get()[0]);
+        let [_, row] = self.locate(&rows, self.cursor);
+        self.move_to(self.position_at(&rows, u16::MAX, row), select)
+    }
+
+    /// Move the cursor to the start of the text
+    pub fn start_of_text (&mut self, select: bool) {
+        self.move_to(0, select)
+    }
+
+    /// Move the cursor to the end of the text
+    pub fn end_of_text (&mut self, select: bool) {
+        self.move_to(self.value.len(), select)
+    }
+
+    /// Insert text at the cursor, replacing the selection.
+    pub fn insert (&mut self, text: &str) {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        self.edit(EditKind::Insert, |this| {
+            this.delete_selection();
+            this.value.insert_str(this.cursor, &text);
+            this.cursor += text.len();
+        });
+    }
+
+    /// Delete the selection, or the grapheme before the cursor. Returns whether anything was deleted.
+    pub fn backspace (&mut self) -> bool {
+        let range = self.selection().unwrap_or(self.prev_grapheme()..self.cursor);
+        self.delete_range(range)
+    }
+
+    /// Delete the selection, or the grapheme after the cursor. Returns whether anything was deleted.
+    pub fn delete (&mut self) -> bool {
+        let range = self.selection().unwrap_or(self.cursor..self.next_grapheme());
+        self.delete_range(range)
+    }
+
+    /// Delete the selection, returning it.
+    pub fn cut (&mut self) -> String {
+        let selected = self.selected().to_string();
+        if let Some(range) = self.selection() {
+            self.last_edit = None;
+            self.delete_range(range);
+        }
+        selected
+    }
+
+    /// Undo the last edit. Returns whether there was one.
+    pub fn undo (&mut self) -> bool {
+        match self.undo.pop() {
+            Some((value, cursor)) => {
+                let current = (std::mem::replace(&mut self.value, value), self.cursor);
+                self.redo.push(current);
+                self.restore(cursor);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Redo the last undone edit. Returns whether there was one.
+    pub fn redo (&mut self) -> bool {
+        match self.redo.pop() {
+            Some((value, cursor)) => {
+                let current = (std::mem::replace(&mut self.value, value), self.cursor);
+                self.undo.push(current);
+                self.restore(cursor);
+                true
+            },
+            None => false
+        }
+    }
+
+    /// Scroll vertically so that the cursor is visible in an area of the given size,
+    /// and return the first visible row.
+    pub fn fit (&self, size: [u16;2]) -> u16 {
+        self.size.set(size);
+        let rows = self.rows(size[0]);
+        let height = size[1].max(1);
+        let [_, row] = self.locate(&rows, self.cursor);
+        let max = (rows.len() as u16).saturating_sub(height);
+        let mut scroll = self.scroll.get().min(max);
+        if row < scroll {
+            scroll = row
+        } else if row >= scroll + height {
+            scroll = row + 1 - height
+        }
+        self.scroll.set(scroll);
+        scroll
+    }
+
+    /// Apply an edit, remembering the previous value so that it can be undone.
+    fn edit (&mut self, kind: EditKind, f: impl FnOnce(&mut Self)) {
+        let before = (self.value.clone(), self.cursor);
+        f(self);
+        if self.value == before.0 {
+            return
+        }
+        if self.last_edit != Some(kind) || kind == EditKind::Other {
+            self.undo.push(before);
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.last_edit = Some(kind);
+        self.column = None;
+    }
+
+    fn restore (&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.value.len());
+        self.anchor = None;
+        self.column = None;
+        self.last_edit = None;
+    }
+
+    fn delete_selection (&mut self) {
+        if let Some(range) = self.selection() {
+            self.cursor = range.start;
+            self.value.replace_range(range, "");
+        }
+        self.anchor = None;
+    }
+
+    fn delete_range (&mut self, range: Range<usize>) -> bool {
+        self.anchor = None;
+        if range.is_empty() {
+            return false
+        }
+        self.edit(EditKind::Delete, |this| {
+            this.cursor = range.start;
+            this.value.replace_range(range, "");
+        });
+        true
+    }
+
+    fn move_rows (&mut self, delta: i32, select: bool) {
+        let rows = self.rows(self.size.get()[0]);
+        let [column, row] = self.locate(&rows, self.cursor);
+        let column = self.column.unwrap_or(column);
+        let target = row as i32 + delta;
+        let position = if target < 0 {
+            0
+        } else if target as usize >= rows.len() {
+            self.value.len()
+        } else {
+            self.position_at(&rows, column, target as u16)
+        };
+        self.move_to(position, select);
+        self.column = Some(column);
+    }
+
+    fn prev_grapheme (&self) -> usize {
+        self.value[..self.cursor].grapheme_indices(true).next_back().map_or(0, |(index, _)|index)
+    }
+
+    fn next_grapheme (&self) -> usize {
+        self.value[self.cursor..].graphemes(true).next().map_or(self.cursor, |g|self.cursor + g.len())
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_edit_multiline_text () {
+        let mut area = TextArea::new().text("the quick brown fox\njumps");
+        assert_eq!(area.rows(10), vec![0..10, 10..19, 20..25]);
+        area.fit([10, 2]);
+        // Vertical movement keeps the column across wrapped rows
+        area.right(false);
+        area.right(false);
+        area.down(1, false);
+        assert_eq!(area.cursor, 12);
+        area.down(1, false);
+        assert_eq!(area.cursor, 22);
+        assert_eq!(area.fit([10, 2]), 1);
+        area.end(false);
+        area.up(1, true);
+        assert_eq!(area.selected(), " fox\njumps");
+        // Typing is undone in one step
+        area.insert("!");
+        area.insert("?");
+        assert_eq!(area.value, "the quick brown!?");
+        area.backspace();
+        assert!(area.undo());
+        assert_eq!(area.value, "the quick brown!?");
+        assert!(area.undo());
+        assert_eq!(area.value, "the quick brown fox\njumps");
+        assert!(area.redo());
+        assert_eq!(area.value, "the quick brown!?");
+        area.select_all();
+        assert_eq!(area.cut(), "the quick brown!?");
+        assert_eq!(area.value, "");
+        // Typing over a selection of everything is undone separately
+        area.insert("a");
+        area.select_all();
+        area.insert("b");
+        assert!(area.undo());
+        assert_eq!(area.value, "a");
+    }
+
+}
@@ -0,0 +1,314 @@
+//! Editable single-line text fields
+
+use std::{cell::Cell, ops::Range};
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Returned from `handle` when the value of a `TextInput` changes or is submitted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextInputMessage {
+    /// The value was edited; this is the new value
+    Change(String),
+    /// The value was submitted, e.g. with Enter. The field is cleared.
+    Submit(String),
+}
+
+/// A single-line text field with a cursor, selection, and optional history.
+///
+/// Positions are byte offsets into the value, and always lie on grapheme boundaries.
+#[derive(Clone, Debug, Default)]
+pub struct TextInput {
+    /// The text being edited
+    pub value:       String,
+    /// Shown when the value is empty
+    pub placeholder: String,
+    /// Position of the cursor
+    pub cursor:      usize,
+    /// The other end of the selection, if there is one
+    pub anchor:      Option<usize>,
+    /// The column of the value at the left edge of the field
+    pub scroll:      Cell<u16>,
+    /// Previously submitted values, oldest first
+    pub history:     Vec<String>,
+    /// How many submitted values to remember. If 0, there is no history.
+    pub history_size: usize,
+    /// Which entry of the history is being edited, if any
+    history_index:   Option<usize>,
+    /// The value that was being edited before browsing the history
+    draft:           String,
+}
+
+impl TextInput {
+
+    /// Create an empty text field
+    pub fn new () -> Self {
+        Self::default()
+    }
+
+    /// Set the text that is shown when the value is empty
+    pub fn placeholder (mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Remember up to `size` submitted values, which can be recalled with Up and Down.
+    pub fn history (mut self, size: usize) -> Self {
+        self.history_size = size;
+        self
+    }
+
+    /// Replace the value, moving the cursor to its end
+    pub fn set_value (&mut self, value: impl Into<String>) {
+        self.value  = value.into();
+        self.cursor = self.value.len();
+        self.anchor = None;
+    }
+
+    /// The selected range of the value, if it's not empty
+    pub fn selection (&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        let range = anchor.min(self.cursor)..anchor.max(self.cursor);
+        if range.is_empty() { None } else { Some(range) }
+    }
+
+    /// The selected text
+    pub fn selected (&self) -> &str {
+        self.selection().map_or("", |range|&self.value[range])
+    }
+
+    /// Move the cursor, extending the selection if `select` is set and clearing it otherwise.
+    pub fn move_to (&mut self, position: usize, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = position.min(self.value.len());
+    }
+
+    /// Select the whole value
+    pub fn select_all (&mut self) {
+        self.anchor = Some(0);
+        self.cursor = self.value.len();
+    }
+
+    /// Move the cursor one grapheme to the left. Without `select`, a selection collapses to its start.
+    pub fn left (&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.start, false),
+            _ => self.move_to(self.prev_grapheme(), select)
+        }
+    }
+
+    /// Move the cursor one grapheme to the right. Without `select`, a selection collapses to its end.
+    pub fn right (&mut self, select: bool) {
+        match self.selection() {
+            Some(range) if !select => self.move_to(range.end, false),
+            _ => self.move_to(self.next_grapheme(), select)
+        }
+    }
+
+    /// Move the cursor to the start of the current or previous word
+    pub fn word_left (&mut self, select: bool) {
+        self.move_to(self.prev_word(), select)
+    }
+
+    /// Move the cursor to the end of the current or next word
+    pub fn word_right (&mut self, select: bool) {
+        self.move_to(self.next_word(), select)
+    }
+
+    /// Move the cursor to the start of the value
+    pub fn home (&mut self, select: bool) {
+        self.move_to(0, select)
+    }
+
+    /// Move the cursor to the end of the value
+    pub fn end (&mut self, select: bool) {
+        self.move_to(self.value.len(), select)
+    }
+
+    /// Insert text at the cursor, replacing the selection. Line breaks are dropped.
+    pub fn insert (&mut self, text: &str) {
+        self.delete_selection();
+        let text: String = text.chars().filter(|c|*c != '\n' && *c != '\r').collect();
+        self.value.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+    }
+
+    /// Delete the selection, or the grapheme before the cursor. Returns whether anything was deleted.
+    pub fn backspace (&mut self) -> bool {
+        self.delete_selection() || self.delete_range(self.prev_grapheme()..self.cursor)
+    }
+
+    /// Delete the selection, or the grapheme after the cursor. Returns whether anything was deleted.
+    pub fn delete (&mut self) -> bool {
+        self.delete_selection() || self.delete_range(self.cursor..self.next_grapheme())
+    }
+
+    /// Delete the selection, or back to the start of the word. Returns whether anything was deleted.
+    pub fn delete_word (&mut self) -> bool {
+        self.delete_selection() || self.delete_range(self.prev_word()..self.cursor)
+    }
+
+    /// Take the value, clearing the field and adding the value to the history.
+    pub fn submit (&mut self) -> String {
+        let value = std::mem::take(&mut self.value);
+        self.cursor = 0;
+        self.anchor = None;
+        self.history_index = None;
+        self.draft.clear();
+        if self.history_size > 0 && !value.is_empty() && self.history.last() != Some(&value) {
+            self.history.push(value.clone());
+            if self.history.len() > self.history_size {
+                self.history.remove(0);
+            }
+        }
+        value
+    }
+
+    /// Replace the value with the previous entry of the history. Returns whether it changed.
+    pub fn history_prev (&mut self) -> bool {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return false,
+            None => {
+                self.draft = self.value.clone();
+                self.history.len() - 1
+            },
+            Some(0) => return false,
+            Some(index) => index - 1
+        };
+        self.history_index = Some(index);
+        self.set_value(self.history[index].clone());
+        true
+    }
+
+    /// Replace the value with the next entry of the history, or with the value
+    /// that was being edited before browsing it. Returns whether it changed.
+    pub fn history_next (&mut self) -> bool {
+        match self.history_index {
+            None => false,
+            Some(index) if index + 1 < self.history.len() => {
+                self.history_index = Some(index + 1);
+                self.set_value(self.history[index + 1].clone());
+                true
+            },
+            Some(_) => {
+                self.history_index = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.set_value(draft);
+                true
+            }
+        }
+    }
+
+    /// The column at which the cursor is drawn
+    pub fn cursor_column (&self) -> u16 {
+        self.value[..self.cursor].width() as u16
+    }
+
+    /// Scroll the field horizontally so that the cursor is visible in the given width,
+    /// and return the scroll offset.
+    pub fn fit (&self, width: u16) -> u16 {
+        let width  = width.max(1);
+        let cursor = self.cursor_column();
+        // Leave room for the cursor after the last character
+        let max    = (self.value.width() as u16 + 1).saturating_sub(width);
+        let mut scroll = self.scroll.get().min(max);
+        if cursor < scroll {
+            scroll = cursor
+        } else if cursor >= scroll + width {
+            scroll = cursor + 1 - width
+        }
+        self.scroll.set(scroll);
+        scroll
+    }
+
+    fn delete_selection (&mut self) -> bool {
+        match self.selection() {
+            Some(range) => self.delete_range(range),
+            None => {
+                self.anchor = None;
+                false
+            }
+        }
+    }
+
+    fn delete_range (&mut self, range: Range<usize>) -> bool {
+        self.anchor = None;
+        if range.is_empty() {
+            return false
+        }
+        self.cursor = range.start;
+        self.value.replace_range(range, "");
+        true
+    }
+
+    fn prev_grapheme (&self) -> usize {
+        self.value[..self.cursor].grapheme_indices(true).next_back().map_or(0, |(index, _)|index)
+    }
+
+    fn next_grapheme (&self) -> usize {
+        self.value[self.cursor..].graphemes(true).next().map_or(self.cursor, |g|self.cursor + g.len())
+    }
+
+    fn prev_word (&self) -> usize {
+        let before = self.value[..self.cursor].trim_end();
+        before.rfind(char::is_whitespace).map_or(0, |index|
+            index + before[index..].chars().next().map_or(0, char::len_utf8))
+    }
+
+    fn next_word (&self) -> usize {
+        let after = &self.value[self.cursor..];
+        let start = after.len() - after.trim_start().len();
+        let end = after[start..].find(char::is_whitespace).map_or(after.len(), |index|start + index);
+        self.cursor + end
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_edit_text () {
+        let mut input = TextInput::new().history(2);
+        input.insert("hello wörld");
+        input.word_left(false);
+        assert_eq!(input.cursor, 6);
+        input.word_right(true);
+        assert_eq!(input.selected(), "wörld");
+        input.insert("there");
+        assert_eq!(input.value, "hello there");
+        input.home(false);
+        input.right(true);
+        input.right(true);
+        assert!(input.delete());
+        assert_eq!(input.value, "llo there");
+        input.end(false);
+        assert!(input.delete_word());
+        assert!(!input.delete());
+        assert_eq!(input.value, "llo ");
+        // Scroll to keep the cursor visible
+        assert_eq!(input.fit(3), 2);
+        input.home(false);
+        assert_eq!(input.fit(3), 0);
+        // Recall submitted values
+        assert_eq!(input.submit(), "llo ");
+        input.insert("one");
+        input.submit();
+        input.insert("draft");
+        assert!(input.history_prev());
+        assert_eq!(input.value, "one");
+        assert!(input.history_prev());
+        assert!(!input.history_prev());
+        assert_eq!(input.value, "llo ");
+        assert!(input.history_next());
+        assert!(input.history_next());
+        assert_eq!(input.value, "draft");
+        assert!(!input.history_next());
+    }
+
+}
@@ -1 +1,52 @@
-pub struct Toggle<T>(pub bool, pub T);
+//! On/off switches
+
+/// How a `Toggle` is drawn.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum ToggleStyle {
+    /// `[x]` when on, `[ ]` when off
+    #[default] Checkbox,
+    /// A switch that slides to the right when on
+    Switch,
+}
+
+/// A labeled switch or checkbox, which flips on Space/Enter or click.
+/// When it changes, the new value is returned from `handle` as the message.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Toggle<T>(
+    /// Whether this toggle is on
+    pub bool,
+    /// The label
+    pub T,
+    /// How this toggle is drawn
+    pub ToggleStyle
+);
+
+impl<T> Toggle<T> {
+    /// Create a checkbox
+    pub fn checkbox (value: bool, label: T) -> Self {
+        Self(value, label, ToggleStyle::Checkbox)
+    }
+    /// Create a switch
+    pub fn switch (value: bool, label: T) -> Self {
+        Self(value, label, ToggleStyle::Switch)
+    }
+    /// Flip the value, returning the new one
+    pub fn toggle (&mut self) -> bool {
+        self.0 = !self.0;
+        self.0
+    }
+    /// Set the value, returning whether it changed
+    pub fn set (&mut self, value: bool) -> bool {
+        std::mem::replace(&mut self.0, value) != value
+    }
+    /// The glyph that shows the current value
+    pub fn glyph (&self) -> &'static str {
+        match (self.2, self.0) {
+            (ToggleStyle::Checkbox, true)  => "[x]",
+            (ToggleStyle::Checkbox, false) => "[ ]",
+            (ToggleStyle::Switch,   true)  => "━●",
+            (ToggleStyle::Switch,   false) => "○━",
+        }
+    }
+}
+
@@ -0,0 +1,276 @@
+//! Hierarchical lists
+
+use super::*;
+
+/// Loads the children of a node when it's first expanded.
+pub type TreeLoader = Box<dyn FnOnce()->Vec<TreeNode>>;
+
+/// A node of a `TreeView`: a label, and children, which may be loaded on demand.
+#[derive(Default)]
+pub struct TreeNode {
+    /// Shown in the tree
+    pub label:    String,
+    /// Whether the children are shown
+    pub expanded: bool,
+    /// The loaded children
+    pub children: Vec<TreeNode>,
+    /// Loads the rest of the children on first expansion
+    loader:       Option<TreeLoader>,
+}
+
+impl std::fmt::Debug for TreeNode {
+    fn fmt (&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TreeNode")
+            .field("label", &self.label)
+            .field("expanded", &self.expanded)
+            .field("children", &self.children)
+            .field("loaded", &self.loader.is_none())
+            .finish()
+    }
+}
+
+impl TreeNode {
+
+    /// Create a node without children
+    pub fn leaf (label: impl Into<String>) -> Self {
+        Self { label: label.into(), ..Self::default() }
+    }
+
+    /// Create a collapsed node with children
+    pub fn new (label: impl Into<String>, children: Vec<TreeNode>) -> Self {
+        Self { label: label.into(), children, ..Self::default() }
+    }
+
+    /// Create a collapsed node whose children are loaded when it's first expanded
+    pub fn lazy (label: impl Into<String>, loader: impl FnOnce()->Vec<TreeNode> + 'static) -> Self {
+        Self { label: label.into(), loader: Some(Box::new(loader)), ..Self::default() }
+    }
+
+    /// Whether the node has, or may have, children
+    pub fn expandable (&self) -> bool {
+        !self.children.is_empty() || self.loader.is_some()
+    }
+
+    /// Show the children, loading them first if needed. Returns whether anything changed.
+    pub fn expand (&mut self) -> bool {
+        if let Some(loader) = self.loader.take() {
+            self.children.extend(loader());
+        }
+        let changed = !self.expanded && !self.children.is_empty();
+        self.expanded = self.expanded || changed;
+        changed
+    }
+
+    /// Hide the children. Returns whether anything changed.
+    pub fn collapse (&mut self) -> bool {
+        std::mem::replace(&mut self.expanded, false)
+    }
+
+}
+
+/// A tree of nodes, which are shown as indented rows and can be expanded and collapsed.
+/// The visible rows are the items of a `List`, so the selection follows the `Focus` API.
+/// When the selection changes, the path to the selected node is returned from `handle`.
+#[derive(Debug, Default)]
+pub struct TreeView {
+    /// The top level nodes
+    pub roots: Vec<TreeNode>,
+    /// The visible rows, as paths of child indices starting from the roots
+    pub rows:  List<Vec<usize>>,
+}
+
+impl TreeView {
+
+    /// Create a tree view, selecting its first node
+    pub fn new (roots: Vec<TreeNode>) -> Self {
+        let mut tree = Self { roots, rows: List::default() };
+        tree.refresh();
+        tree.rows.select(0);
+        tree
+    }
+
+    /// The node at a path
+    pub fn node (&self, path: &[usize]) -> Option<&TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.roots.get(*first)?, |node, index|node.children.get(*index))
+    }
+
+    /// The node at a path, mutably
+    pub fn node_mut (&mut self, path: &[usize]) -> Option<&mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        rest.iter().try_fold(self.roots.get_mut(*first)?, |node, index|node.children.get_mut(*index))
+    }
+
+    /// The path to the selected node
+    pub fn selected_path (&self) -> Option<Vec<usize>> {
+        self.rows.get().cloned()
+    }
+
+    /// Expand the selected node, or, if it's already expanded, select its first child.
+    /// Returns whether anything changed.
+    pub fn expand (&mut self) -> bool {
+        let (Some(path), Some(index)) = (self.selected_path(), self.selected()) else { return false };
+        match self.node_mut(&path) {
+            Some(node) if node.expanded => {
+                let has_children = !node.children.is_empty();
+                has_children && self.select(index + 1)
+            },
+            Some(node) => {
+                let changed = node.expand();
+                self.refresh();
+                changed
+            },
+            None => false
+        }
+    }
+
+    /// Collapse the selected node, or, if it's already collapsed, select its parent.
+    /// Returns whether anything changed.
+    pub fn collapse (&mut self) -> bool {
+        let Some(path) = self.selected_path() else { return false };
+        if self.node_mut(&path).is_some_and(|node|node.collapse()) {
+            self.refresh();
+            return true
+        }
+        match self.rows.iter().position(|row|row[..] == path[..path.len() - 1]) {
+            Some(parent) => self.select(parent),
+            None => false
+        }
+    }
+
+    /// Expand the selected node if it's collapsed, and collapse it otherwise.
+    /// Returns whether anything changed.
+    pub fn toggle (&mut self) -> bool {
+        let path = self.selected_path();
+        let changed = match path.as_deref().and_then(|path|self.node_mut(path)) {
+            Some(node) if node.expanded => node.collapse(),
+            Some(node) => node.expand(),
+            None => false
+        };
+        if changed {
+            self.refresh();
+        }
+        changed
+    }
+
+    /// List the visible rows again, after nodes were expanded or collapsed.
+    /// The selected node stays selected, or, if it was hidden, its nearest visible ancestor.
+    pub fn refresh (&mut self) {
+        let selected = self.selected_path();
+        let mut rows = vec![];
+        fn visit (nodes: &[TreeNode], path: &mut Vec<usize>, rows: &mut Vec<Vec<usize>>) {
+            for (index, node) in nodes.iter().enumerate() {
+                path.push(index);
+                rows.push(path.clone());
+                if node.expanded {
+                    visit(&node.children, path, rows);
+                }
+                path.pop();
+            }
+        }
+        visit(&self.roots, &mut vec![], &mut rows);
+        self.rows.replace(rows);
+        if let Some(mut path) = selected {
+            while !path.is_empty() {
+                if let Some(index) = self.rows.iter().position(|row|*row == path) {
+                    self.rows.select(index);
+                    break
+                }
+                path.pop();
+            }
+        }
+    }
+
+    /// The indentation guides in front of a node, ending with a marker that shows
+    /// whether it's expanded, collapsed, or has no children.
+    pub fn prefix (&self, path: &[usize]) -> String {
+        let mut prefix = String::new();
+        // Whether the node at each depth is the last of its siblings
+        let last = |depth: usize| {
+            let siblings = match depth {
+                0 => self.roots.len(),
+                _ => self.node(&path[..depth]).map_or(0, |parent|parent.children.len())
+            };
+            path[depth] + 1 >= siblings
+        };
+        for depth in 1..path.len() {
+            let final_depth = depth == path.len() - 1;
+            prefix.push_str(match (final_depth, last(depth)) {
+                (false, false) => "│ ",
+                (false, true)  => "  ",
+                (true,  false) => "├─",
+                (true,  true)  => "└─",
+            });
+        }
+        let node = self.node(path);
+        prefix.push_str(match node.map(|node|(node.expandable(), node.expanded)) {
+            Some((true, true))  => "▾ ",
+            Some((true, false)) => "▸ ",
+            _ if path.len() > 1 => "─ ",
+            _ => "  ",
+        });
+        prefix
+    }
+
+}
+
+impl Focus<Vec<usize>> for TreeView {
+
+    fn items (&self) -> &Vec<Vec<usize>> {
+        self.rows.items()
+    }
+
+    fn items_mut (&mut self) -> &mut Vec<Vec<usize>> {
+        self.rows.items_mut()
+    }
+
+    fn state (&self) -> &FocusState<usize> {
+        self.rows.state()
+    }
+
+    fn state_mut (&mut self) -> &mut FocusState<usize> {
+        self.rows.state_mut()
+    }
+
+}
+
+#[cfg(test)]
+mod test {
+    use crate::layouts::*;
+
+    #[test]
+    fn should_expand_and_collapse_nodes () {
+        let mut tree = TreeView::new(vec![
+            TreeNode::lazy("src", ||vec![
+                TreeNode::new("layouts", vec![TreeNode::leaf("tree.rs")]),
+                TreeNode::leaf("lib.rs"),
+            ]),
+            TreeNode::leaf("Cargo.toml"),
+        ]);
+        assert_eq!(tree.len(), 2);
+        assert!(tree.expand());
+        assert_eq!(tree.len(), 4);
+        assert!(tree.expand());
+        assert_eq!(tree.selected_path(), Some(vec![0, 0]));
+        assert!(tree.expand());
+        let prefixes: Vec<String> = tree.iter().map(|path|tree.prefix(path)).collect();
+        assert_eq!(prefixes, ["▾ ", "├─▾ ", "│ └── ", "└── ", "  "]);
+        // Left on a leaf goes to the parent, then collapses it
+        tree.select(2);
+        assert!(tree.collapse());
+        assert_eq!(tree.selected_path(), Some(vec![0, 0]));
+        assert!(tree.collapse());
+        assert_eq!(tree.len(), 4);
+        // Toggling a leaf changes nothing
+        tree.select(3);
+        assert!(!tree.toggle());
+        // Hiding the selected node selects its nearest visible ancestor
+        tree.expand();
+        tree.select(2);
+        tree.node_mut(&[0]).map(TreeNode::collapse);
+        tree.refresh();
+        assert_eq!(tree.selected_path(), Some(vec![0]));
+        assert_eq!(tree.len(), 2);
+    }
+
+}
@@ -10,6 +10,12 @@ pub use input::*;
 mod output;
 pub use output::*;
 
+mod tasks;
+pub use tasks::*;
+
+use std::future::Future;
+use std::time::Duration;
+
 /// Standard result type. Shorthand for `Result<T, Box<dyn std::error::Error>>`
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -35,6 +41,38 @@ pub trait Context {
 
     fn exited (&self) -> bool;
 
+    /// Whether the UI needs to be rendered again.
+    /// Engines that don't track this always rerender.
+    fn dirty (&self) -> bool {
+        true
+    }
+
+    /// Ask for the UI to be rendered again on the next iteration.
+    fn request_redraw (&mut self) {}
+
+    /// Have the widgets handle a tick once, after `delay` has passed.
+    /// Engines without timers return an error.
+    fn set_timeout (&mut self, _delay: Duration) -> Result<TimerId> {
+        Err("this engine has no timers".into())
+    }
+
+    /// Have the widgets handle a tick every `period`, until the timer is cleared.
+    /// Engines without timers return an error.
+    fn set_interval (&mut self, _period: Duration) -> Result<TimerId> {
+        Err("this engine has no timers".into())
+    }
+
+    /// Stop a timer from firing.
+    fn clear_timer (&mut self, _id: TimerId) {}
+
+    /// Pass input to the items of a container; see `layouts::dispatch`.
+    /// Engines can override this to route some events, e.g. clicks, by position.
+    fn route <'a, U> (&mut self, items: &mut [Collected<'a, Self, U>], selected: Option<usize>)
+        -> Result<Option<U>> where Self: Sized
+    {
+        layouts::dispatch(items, selected, self)
+    }
+
 }
 
 impl<X, A> MainLoop<A> for X where
@@ -44,7 +82,9 @@ impl<X, A> MainLoop<A> for X where
     fn run (mut self, mut context: A) -> Result<A> {
         context.setup()?;
         loop {
-            context.render(&self)?;
+            if context.dirty() {
+                context.render(&self)?;
+            }
             context.handle(&mut self)?;
             if context.exited() {
                 break
@@ -54,6 +94,46 @@ impl<X, A> MainLoop<A> for X where
     }
 }
 
+/// Provides the entry point into an asynchronous main loop,
+/// which can be woken by input events, timers, and spawned futures.
+///
+/// Use `block_on` to run it without pulling in an async runtime.
+#[allow(async_fn_in_trait)]
+pub trait AsyncMainLoop<T> {
+    async fn run_async (self, context: T) -> Result<T>;
+}
+
+/// Trait for engines that can wait for input without blocking.
+#[allow(async_fn_in_trait)]
+pub trait AsyncContext: Context {
+    /// Wait for the next input event or spawned task, and handle it.
+    async fn handle_async (&mut self, _: &mut impl Input<Self, Self::Handled>)
+        -> Result<()> where Self: Sized;
+
+    /// Run a future alongside the main loop.
+    /// The UI is rerendered whenever a spawned future completes.
+    fn spawn (&mut self, _: impl Future<Output = ()> + 'static);
+}
+
+impl<X, A> AsyncMainLoop<A> for X where
+    A: AsyncContext,
+    X: Input<A, A::Handled> + Output<A, A::Rendered>
+{
+    async fn run_async (mut self, mut context: A) -> Result<A> {
+        context.setup()?;
+        loop {
+            if context.dirty() {
+                context.render(&self)?;
+            }
+            context.handle_async(&mut self).await?;
+            if context.exited() {
+                break
+            }
+        }
+        Ok(context)
+    }
+}
+
 pub trait Widget<A>: Input<A, A::Handled> + Output<A, A::Rendered> where
     A: Context
 {}
@@ -1,12 +1,25 @@
 
-use crate::*;
+use crate::{*, layouts::Flex};
 
 use std::fmt::{Debug, Formatter};
 
-/// Displays state to the user, in an engine-specific way
+/// Displays state to the user, in an engine-specific way.
+///
+/// Layout happens in three phases: containers `measure` their items against
+/// the engine's current area, arrange them by assigning each one an area,
+/// then `render` them into it.
 pub trait Output<T, U> {
     /// Render this component
     fn render (&self, engine: &mut T) -> Result<Option<U>>;
+    /// Measure this component without rendering it, within the engine's current area.
+    /// `None` means the size is not known until the component is rendered.
+    fn measure (&self, _engine: &mut T) -> Result<Option<U>> {
+        Ok(None)
+    }
+    /// How this component is sized along the axis of a `Rows` or `Columns`.
+    fn flex (&self) -> Flex {
+        Flex::Auto
+    }
     /// Wrap this output in the appropriate `Collected` variant.
     fn into_collected <'a> (self) -> Collected<'a, T, U> where Self: Sized + 'a {
         Collected::Box(Box::new(self))
@@ -18,6 +31,12 @@ impl<T, U, V: Output<T, U>> Output<T, U> for &V {
     fn render (&self, engine: &mut T) -> Result<Option<U>> {
         (*self).render(engine)
     }
+    fn measure (&self, engine: &mut T) -> Result<Option<U>> {
+        (*self).measure(engine)
+    }
+    fn flex (&self) -> Flex {
+        (*self).flex()
+    }
     /// References to items are added as `Collected::Ref`.
     fn into_collected <'a> (self) -> Collected<'a, T, U> where Self: Sized + 'a {
         Collected::Ref(self)
@@ -29,6 +48,12 @@ impl<T, U, V: Output<T, U>> Output<T, U> for &mut V {
     fn render (&self, engine: &mut T) -> Result<Option<U>> {
         (**self).render(engine)
     }
+    fn measure (&self, engine: &mut T) -> Result<Option<U>> {
+        (**self).measure(engine)
+    }
+    fn flex (&self) -> Flex {
+        (**self).flex()
+    }
     /// Mutable references to items are added as `Collected::Ref`.
     fn into_collected <'a> (self) -> Collected<'a, T, U> where Self: Sized + 'a {
         Collected::Ref(self)
@@ -40,6 +65,12 @@ impl<'a, T, U> Output<T, U> for Box<dyn Output<T, U> + 'a> {
     fn render (&self, engine: &mut T) -> Result<Option<U>> {
         (**self).render(engine)
     }
+    fn measure (&self, engine: &mut T) -> Result<Option<U>> {
+        (**self).measure(engine)
+    }
+    fn flex (&self) -> Flex {
+        (**self).flex()
+    }
     /// Boxed items are added as `Collected::Box`.
     fn into_collected <'b> (self) -> Collected<'b, T, U> where Self: Sized + 'b {
         Collected::Box(self)
@@ -55,19 +86,43 @@ impl<T, U, V: Output<T, U>> Output<T, U> for Option<V> {
             None => Ok(None)
         }
     }
+    fn measure (&self, engine: &mut T) -> Result<Option<U>> {
+        match self {
+            Some(widget) => widget.measure(engine),
+            None => Ok(None)
+        }
+    }
+    fn flex (&self) -> Flex {
+        match self {
+            Some(widget) => widget.flex(),
+            None => Flex::Auto
+        }
+    }
 }
 
 /// A collection of widgets.
+///
+/// A layout is built twice: once from shared references or owned widgets with `add`,
+/// to be rendered, and once from mutable references with `add_mut`, to handle input.
+/// Both trees must add the same widgets in the same order, so that hit-testing paths match.
 pub trait Collection<'a, T, U> {
+    /// Add an item to be rendered.
     fn add (self, widget: impl Output<T, U> + 'a) -> Self;
+    /// Add an item to receive input. Here `U` is the type of message it returns.
+    fn add_mut (self, widget: &'a mut (impl Input<T, U> + 'a)) -> Self;
 }
 
 /// Wrapper that allows owned and borrowed items to be treated similarly.
 ///
+/// `U` is what the items return: their size when rendering,
+/// or their message when handling input. A collection is built
+/// with `add` in `render`, and with `add_mut` in `handle`.
+///
 /// Thanks @steffahn for pointing me in the right direction!
 pub enum Collected<'a, T, U> {
     Box(Box<dyn Output<T, U> + 'a>),
     Ref(&'a (dyn Output<T, U> + 'a)),
+    Mut(&'a mut (dyn Input<T, U> + 'a)),
     None
 }
 
@@ -76,6 +131,7 @@ impl<'a, T, U> Debug for Collected<'a, T, U> {
         write!(f, "Collected({})", match self {
             Self::Box(_) => "Box",
             Self::Ref(_) => "Ref",
+            Self::Mut(_) => "Mut",
             Self::None   => "Nil.",
         })
     }
@@ -86,9 +142,32 @@ impl<'a, T, U> Output<T, U> for Collected<'a, T, U> {
         Ok(match self {
             Self::Box(item) => (*item).render(engine)?,
             Self::Ref(item) => (*item).render(engine)?,
-            Self::None => None
+            Self::Mut(_) | Self::None => None
         })
     }
+    fn measure (&self, engine: &mut T) -> Result<Option<U>> {
+        Ok(match self {
+            Self::Box(item) => (*item).measure(engine)?,
+            Self::Ref(item) => (*item).measure(engine)?,
+            Self::Mut(_) | Self::None => None
+        })
+    }
+    fn flex (&self) -> Flex {
+        match self {
+            Self::Box(item) => (*item).flex(),
+            Self::Ref(item) => (*item).flex(),
+            Self::Mut(_) | Self::None => Flex::Auto
+        }
+    }
+}
+
+impl<'a, T, U> Input<T, U> for Collected<'a, T, U> {
+    fn handle (&mut self, engine: &mut T) -> Result<Option<U>> {
+        match self {
+            Self::Mut(item) => item.handle(engine),
+            _ => Ok(None)
+        }
+    }
 }
 
 impl<'a, T, U> Collector<'a, T, U> {
@@ -0,0 +1,246 @@
+//! A minimal executor for the asynchronous main loop, and timers.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    fmt::{Debug, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, mpsc::{channel, Sender, Receiver, RecvTimeoutError}},
+    task::{Context as TaskContext, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
+};
+
+/// Run a future to completion, parking the current thread while it's pending.
+pub fn block_on <F: Future> (future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = TaskContext::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output
+        }
+        thread::park();
+    }
+}
+
+/// Unparks the thread that is running `block_on`.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake (self: Arc<Self>) {
+        self.0.unpark()
+    }
+    fn wake_by_ref (self: &Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+/// Holds the waker of the task that runs the main loop,
+/// so that other threads (e.g. input) can wake it after sending an event.
+#[derive(Clone, Default)]
+pub struct WakeHandle(Arc<Mutex<Option<Waker>>>);
+
+impl WakeHandle {
+    /// Store the waker to call on the next wakeup
+    pub fn register (&self, waker: &Waker) {
+        let mut slot = self.0.lock().unwrap();
+        if !slot.as_ref().is_some_and(|current|current.will_wake(waker)) {
+            *slot = Some(waker.clone());
+        }
+    }
+    /// Wake the main loop, if it's waiting
+    pub fn wake (&self) {
+        if let Some(waker) = self.0.lock().unwrap().as_ref() {
+            waker.wake_by_ref()
+        }
+    }
+}
+
+impl Debug for WakeHandle {
+    fn fmt (&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "WakeHandle")
+    }
+}
+
+/// Futures that run alongside the main loop.
+#[derive(Default)]
+pub struct Tasks(Vec<Pin<Box<dyn Future<Output = ()>>>>);
+
+impl Tasks {
+    /// Add a future to be polled by the main loop
+    pub fn spawn (&mut self, future: impl Future<Output = ()> + 'static) {
+        self.0.push(Box::pin(future))
+    }
+    /// Whether there are no pending tasks
+    pub fn is_empty (&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Poll each task once, dropping the finished ones. Returns how many finished.
+    pub fn poll (&mut self, cx: &mut TaskContext) -> usize {
+        let pending = self.0.len();
+        self.0.retain_mut(|task|task.as_mut().poll(cx).is_pending());
+        pending - self.0.len()
+    }
+}
+
+impl Debug for Tasks {
+    fn fmt (&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "Tasks({})", self.0.len())
+    }
+}
+
+/// Identifies a timer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(usize);
+
+/// Sent to the thread that fires the timers.
+enum TimerCommand {
+    /// Fire a timer after a period, and, if it repeats, every period after that
+    Set(TimerId, Duration, bool),
+    /// Stop firing a timer
+    Clear(TimerId),
+}
+
+/// Called from the timer thread with each timer that fires.
+/// Returning `false` stops the timer thread.
+type TimerCallback = Box<dyn FnMut(TimerId)->bool + Send>;
+
+/// The timers of an engine. They are all fired from a single thread,
+/// which is started with the first timer, and sleeps until the nearest deadline.
+pub struct Timers {
+    /// Called when a timer fires, until the thread is started
+    fire:     Option<TimerCallback>,
+    /// Sends commands to the timer thread, once it's started
+    commands: Option<Sender<TimerCommand>>,
+    /// The active timers, and whether they repeat
+    active:   HashMap<TimerId, bool>,
+    /// ID of the next timer to be set
+    next:     usize,
+}
+
+impl Timers {
+
+    /// Create a set of timers which call `fire` when they fire
+    pub fn new (fire: impl FnMut(TimerId)->bool + Send + 'static) -> Self {
+        Self { fire: Some(Box::new(fire)), commands: None, active: HashMap::new(), next: 0 }
+    }
+
+    /// Start a timer that fires after `period`, and, if `repeat` is set, every `period` after that
+    pub fn set (&mut self, period: Duration, repeat: bool) -> TimerId {
+        let id = TimerId(self.next);
+        self.next += 1;
+        self.active.insert(id, repeat);
+        if self.commands.is_none() {
+            let (commands, receiver) = channel();
+            if let Some(fire) = self.fire.take() {
+                thread::spawn(move ||Self::run(receiver, fire));
+            }
+            self.commands = Some(commands);
+        }
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(TimerCommand::Set(id, period, repeat));
+        }
+        id
+    }
+
+    /// Stop a timer from firing. Returns whether it was active.
+    pub fn clear (&mut self, id: TimerId) -> bool {
+        if let Some(commands) = &self.commands {
+            let _ = commands.send(TimerCommand::Clear(id));
+        }
+        self.active.remove(&id).is_some()
+    }
+
+    /// Whether a timer is active
+    pub fn contains (&self, id: TimerId) -> bool {
+        self.active.contains_key(&id)
+    }
+
+    /// Note that a timer has fired, forgetting it if it doesn't repeat.
+    /// Returns `false` if it has been cleared since, and should be ignored.
+    pub fn fired (&mut self, id: TimerId) -> bool {
+        match self.active.get(&id) {
+            Some(true)  => true,
+            Some(false) => self.active.remove(&id).is_some(),
+            None        => false
+        }
+    }
+
+    /// Fire timers as their deadlines pass, until the `Timers` are dropped
+    /// or the callback asks to stop.
+    fn run (commands: Receiver<TimerCommand>, mut fire: TimerCallback) {
+        let mut deadlines = BinaryHeap::<Reverse<(Instant, TimerId)>>::new();
+        let mut periods = HashMap::new();
+        loop {
+            let command = match deadlines.peek() {
+                Some(Reverse((deadline, _))) =>
+                    commands.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                None =>
+                    commands.recv().map_err(|_|RecvTimeoutError::Disconnected)
+            };
+            match command {
+                Ok(TimerCommand::Set(id, period, repeat)) => {
+                    periods.insert(id, (period, repeat));
+                    deadlines.push(Reverse((Instant::now() + period, id)));
+                },
+                Ok(TimerCommand::Clear(id)) => {
+                    // Its deadlines are skipped when they come up
+                    periods.remove(&id);
+                },
+                Err(RecvTimeoutError::Timeout) => {
+                    let now = Instant::now();
+                    while let Some(&Reverse((deadline, id))) = deadlines.peek() {
+                        if deadline > now {
+                            break
+                        }
+                        deadlines.pop();
+                        let Some(&(period, repeat)) = periods.get(&id) else { continue };
+                        if !fire(id) {
+                            return
+                        }
+                        if repeat {
+                            // Schedule from the previous deadline so that intervals don't drift
+                            deadlines.push(Reverse((deadline + period.max(Duration::from_millis(1)), id)));
+                        } else {
+                            periods.remove(&id);
+                        }
+                    }
+                },
+                Err(RecvTimeoutError::Disconnected) => return
+            }
+        }
+    }
+
+}
+
+impl Debug for Timers {
+    fn fmt (&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "Timers({})", self.active.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use std::{sync::mpsc::channel, time::Duration};
+
+    #[test]
+    fn should_fire_timers_from_one_thread () {
+        let (tx, rx) = channel();
+        let mut timers = Timers::new(move |id|tx.send((id, std::thread::current().id())).is_ok());
+        let interval = timers.set(Duration::from_millis(2), true);
+        let timeout = timers.set(Duration::from_millis(1), false);
+        let cleared = timers.set(Duration::from_millis(1), false);
+        timers.clear(cleared);
+        let fired: Vec<_> = (0..3).map(|_|rx.recv_timeout(Duration::from_secs(5)).unwrap()).collect();
+        assert_eq!(fired.iter().filter(|(id, _)|*id == interval).count(), 2);
+        assert!(fired.iter().any(|(id, _)|*id == timeout));
+        assert!(fired.iter().all(|(id, thread)|*id != cleared && *thread == fired[0].1));
+        assert!(timers.fired(timeout));
+        assert!(!timers.contains(timeout));
+        assert!(!timers.fired(cleared));
+    }
+
+}
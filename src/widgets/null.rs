@@ -36,6 +36,12 @@ impl<T: Output<(), ()>> Output<(), ()> for Offset<u16, T> {
     }
 }
 
+impl<T: Output<(), ()>> Output<(), ()> for Aligned<T> {
+    fn render (&self, engine: &mut ()) -> Result<Option<()>> {
+        self.1.render(engine)
+    }
+}
+
 impl<'a> Output<(), ()> for Rows<'a, (), ()> {
     fn render (&self, _: &mut ()) -> Result<Option<()>> {
         Ok(None)
@@ -53,3 +59,108 @@ impl<'a> Output<(), ()> for Layers<'a, (), ()> {
         Ok(None)
     }
 }
+
+impl<T: Output<(), ()>> Output<(), ()> for Scrollable<T> {
+    fn render (&self, engine: &mut ()) -> Result<Option<()>> {
+        self.get().render(engine)
+    }
+}
+
+impl<U, V: Input<(), U>> Input<(), U> for Scrollable<V> {
+    fn handle (&mut self, engine: &mut ()) -> Result<Option<U>> {
+        self.get_mut().handle(engine)
+    }
+}
+
+impl<T: Output<(), ()>> Output<(), ()> for Toggle<T> {
+    fn render (&self, engine: &mut ()) -> Result<Option<()>> {
+        self.1.render(engine)
+    }
+}
+
+impl<T> Input<(), bool> for Toggle<T> {
+    fn handle (&mut self, _: &mut ()) -> Result<Option<bool>> {
+        Ok(None)
+    }
+}
+
+impl<T: Output<(), ()>> Output<(), ()> for List<T> {
+    fn render (&self, engine: &mut ()) -> Result<Option<()>> {
+        for item in self.iter() {
+            item.render(engine)?;
+        }
+        Ok(None)
+    }
+}
+
+impl<T> Input<(), usize> for List<T> {
+    fn handle (&mut self, _: &mut ()) -> Result<Option<usize>> {
+        Ok(None)
+    }
+}
+
+impl Output<(), ()> for Table {
+    fn render (&self, _: &mut ()) -> Result<Option<()>> {
+        Ok(None)
+    }
+}
+
+impl Input<(), usize> for Table {
+    fn handle (&mut self, _: &mut ()) -> Result<Option<usize>> {
+        Ok(None)
+    }
+}
+
+impl Output<(), ()> for TreeView {
+    fn render (&self, _: &mut ()) -> Result<Option<()>> {
+        Ok(None)
+    }
+}
+
+impl Input<(), Vec<usize>> for TreeView {
+    fn handle (&mut self, _: &mut ()) -> Result<Option<Vec<usize>>> {
+        Ok(None)
+    }
+}
+
+impl Output<(), ()> for TextArea {
+    fn render (&self, _: &mut ()) -> Result<Option<()>> {
+        Ok(None)
+    }
+}
+
+impl Input<(), TextInputMessage> for TextArea {
+    fn handle (&mut self, _: &mut ()) -> Result<Option<TextInputMessage>> {
+        Ok(None)
+    }
+}
+
+impl Output<(), ()> for TextInput {
+    fn render (&self, _: &mut ()) -> Result<Option<()>> {
+        Ok(None)
+    }
+}
+
+impl Input<(), TextInputMessage> for TextInput {
+    fn handle (&mut self, _: &mut ()) -> Result<Option<TextInputMessage>> {
+        Ok(None)
+    }
+}
+
+impl<S: AsRef<str>> Output<(), ()> for Paragraph<S> {
+    fn render (&self, _: &mut ()) -> Result<Option<()>> {
+        Ok(None)
+    }
+}
+
+impl<T: Output<(), ()>> Output<(), ()> for Styled<T> {
+    fn render (&self, engine: &mut ()) -> Result<Option<()>> {
+        self.1.render(engine)
+    }
+}
+
+impl<T: AsRef<str>> Output<(), ()> for Line<T> {
+    fn render (&self, _: &mut ()) -> Result<Option<()>> {
+        Ok(None)
+    }
+}
@@ -1,15 +1,366 @@
-use crate::{*, engines::repl::*};
+use crate::{*, layouts::*, engines::repl::*};
 use std::io::{BufRead, Write};
+use unicode_width::UnicodeWidthStr;
 
 impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for String {
     fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
         self.as_str().render(engine)
     }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        self.as_str().measure(engine)
+    }
 }
 
 impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for &str {
     fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
         engine.write(self.as_bytes())?;
-        Ok(Some([self.len() as u16, 1]))
+        self.measure(engine)
+    }
+    fn measure (&self, _: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        Ok(Some([UnicodeWidthStr::width(*self) as u16, 1]))
+    }
+}
+
+/// Styles are not displayed.
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Styled<T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        self.1.render(engine)
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        self.1.measure(engine)
+    }
+}
+
+/// The spans of a line are written as plain text.
+impl<R: BufRead, W: Write, T: AsRef<str>> Output<Repl<R, W>, [u16;2]> for Line<T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        for span in self.0.iter() {
+            engine.write(span.1.as_ref().as_bytes())?;
+        }
+        self.measure(engine)
+    }
+    fn measure (&self, _: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        Ok(Some([self.0.iter().map(|span|UnicodeWidthStr::width(span.1.as_ref()) as u16).sum(), 1]))
+    }
+}
+
+/// Lines of a paragraph are written one after the other, indented to align them.
+impl<R: BufRead, W: Write, S: AsRef<str>> Output<Repl<R, W>, [u16;2]> for Paragraph<S> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        for (index, (dx, line)) in self.lines(engine.area.w(), engine.area.h()).iter().enumerate() {
+            if index > 0 {
+                engine.write(b"\n")?;
+            }
+            engine.pad(*dx)?;
+            engine.write(line.as_bytes())?;
+        }
+        self.measure(engine)
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        Ok(Some(self.size(engine.area.w(), engine.area.h())))
+    }
+}
+
+/// Pad the rendered width of an item up to the width that it's supposed to have.
+fn pad_to <R, W: Write> (
+    engine: &mut Repl<R, W>, size: Option<[u16;2]>, outer: Option<[u16;2]>
+) -> Result<Option<[u16;2]>> {
+    if let (Some([w, _]), Some([outer_w, _])) = (size, outer) {
+        engine.pad(outer_w.saturating_sub(w))?;
+    }
+    Ok(outer)
+}
+
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Fixed<u16, T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().render(engine))?;
+        pad_to(engine, size, self.outer(size))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().measure(engine))?;
+        Ok(self.outer(size))
+    }
+}
+
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Min<u16, T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().render(engine))?;
+        pad_to(engine, size, self.outer(size))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().measure(engine))?;
+        Ok(self.outer(size))
+    }
+}
+
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Max<u16, T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().render(engine))?;
+        Ok(self.outer(size))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().measure(engine))?;
+        Ok(self.outer(size))
+    }
+}
+
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Offset<u16, T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        engine.write("\n".repeat(self.1 as usize).as_bytes())?;
+        engine.pad(self.0)?;
+        let size = engine.with_area(self.inner(engine.area), |engine|self.2.render(engine))?;
+        Ok(self.outer(size))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.2.measure(engine))?;
+        Ok(self.outer(size))
+    }
+}
+
+/// Only horizontal alignment applies to lines of text.
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Aligned<T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let area = engine.area;
+        let size = engine.with_area(area, |engine|self.1.measure(engine))?;
+        let dx = size.map_or(0, |size|self.0.place(area, size).x() - area.x());
+        engine.pad(dx)?;
+        let size = engine.with_area(area, |engine|self.1.render(engine))?;
+        let [w, h] = size.unwrap_or([0, 1]);
+        engine.pad(area.w().saturating_sub(dx + w))?;
+        Ok(Some([area.w(), h]))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let h = engine.with_area(engine.area, |engine|self.1.measure(engine))?.map_or(1, |[_, h]|h);
+        Ok(Some([engine.area.w(), h]))
+    }
+}
+
+impl<'a, R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for Rows<'a, Repl<R, W>, [u16;2]> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let mut x = 0;
+        let mut y = 0;
+        let area = engine.area;
+        for (index, item) in self.0.iter().enumerate() {
+            if index > 0 {
+                engine.write(b"\n")?;
+            }
+            let [w, h] = engine.with_area(area, |engine|item.render(engine))?.unwrap_or([0, 1]);
+            x = x.max(w);
+            y += h;
+        }
+        Ok(Some([x, y]))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let mut x = 0;
+        let mut y = 0;
+        let area = engine.area;
+        for item in self.0.iter() {
+            match engine.with_area(area, |engine|item.measure(engine))? {
+                Some([w, h]) => { x = x.max(w); y += h; },
+                None => return Ok(None)
+            }
+        }
+        Ok(Some([x, y]))
+    }
+}
+
+impl<'a, R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for Columns<'a, Repl<R, W>, [u16;2]> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let mut x = 0;
+        let mut y = 0;
+        let area = engine.area;
+        for item in self.0.iter() {
+            // Pad each column to its measured width, so that the next one lines up
+            let free = [area.x() + x, area.y(), area.w().saturating_sub(x), area.h()];
+            let size = engine.with_area(free, |engine|item.measure(engine))?;
+            let rendered = engine.with_area(free, |engine|item.render(engine))?;
+            let [w, h] = pad_to(engine, rendered, size.or(rendered))?.unwrap_or([0, 0]);
+            x += w;
+            y = y.max(h);
+        }
+        Ok(Some([x, y]))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let mut x = 0;
+        let mut y = 0;
+        let area = engine.area;
+        for item in self.0.iter() {
+            let free = [area.x() + x, area.y(), area.w().saturating_sub(x), area.h()];
+            match engine.with_area(free, |engine|item.measure(engine))? {
+                Some([w, h]) => { x += w; y = y.max(h); },
+                None => return Ok(None)
+            }
+        }
+        Ok(Some([x, y]))
+    }
+}
+
+/// The REPL prints the whole content of a scrollable.
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Scrollable<T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        self.get().render(engine)
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        self.get().measure(engine)
+    }
+}
+
+impl<R: BufRead, W: Write, U, V: Input<Repl<R, W>, U>> Input<Repl<R, W>, U> for Scrollable<V> {
+    fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<U>> {
+        self.get_mut().handle(engine)
+    }
+}
+
+/// A toggle is a yes/no prompt. The capitalized answer is the current value.
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for Toggle<T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let [w, h] = self.1.render(engine)?.unwrap_or([0, 1]);
+        let prompt = if self.0 { " [Y/n] " } else { " [y/N] " };
+        engine.write(prompt.as_bytes())?;
+        Ok(Some([w + prompt.len() as u16, h]))
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        Ok(self.1.measure(engine)?.map(|[w, h]|[w + 7, h]))
+    }
+}
+
+/// Answering yes or no sets the value; if it changed, the new value is returned.
+impl<R: BufRead, W: Write, T> Input<Repl<R, W>, bool> for Toggle<T> {
+    fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<bool>> {
+        let value = match engine.event.as_deref().map(|line|line.trim().to_lowercase()).as_deref() {
+            Some("y" | "yes") => true,
+            Some("n" | "no")  => false,
+            _ => return Ok(None)
+        };
+        Ok(if self.set(value) { Some(value) } else { None })
+    }
+}
+
+/// A text field is shown as its value, or as its placeholder when empty.
+impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for TextInput {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let text = if self.value.is_empty() { &self.placeholder } else { &self.value };
+        text.render(engine)
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let text = if self.value.is_empty() { &self.placeholder } else { &self.value };
+        text.measure(engine)
+    }
+}
+
+/// Each line that is entered is submitted as the value.
+impl<R: BufRead, W: Write> Input<Repl<R, W>, TextInputMessage> for TextInput {
+    fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<TextInputMessage>> {
+        Ok(match engine.event.as_deref() {
+            Some(line) => {
+                self.set_value(line.trim_end_matches(['\r', '\n']));
+                Some(TextInputMessage::Submit(self.submit()))
+            },
+            None => None
+        })
+    }
+}
+
+impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for TextArea {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        Paragraph::new(&self.value).render(engine)
+    }
+    fn measure (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        Paragraph::new(&self.value).measure(engine)
+    }
+}
+
+/// Each line that is entered is appended to the text.
+impl<R: BufRead, W: Write> Input<Repl<R, W>, TextInputMessage> for TextArea {
+    fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<TextInputMessage>> {
+        Ok(match engine.event.as_deref() {
+            Some(line) => {
+                self.end_of_text(false);
+                if !self.value.is_empty() {
+                    self.insert("\n");
+                }
+                self.insert(line.trim_end_matches(['\r', '\n']));
+                Some(TextInputMessage::Change(self.value.clone()))
+            },
+            None => None
+        })
+    }
+}
+
+/// A list is shown as numbered choices, with the selected one marked.
+impl<R: BufRead, W: Write, T: Output<Repl<R, W>, [u16;2]>> Output<Repl<R, W>, [u16;2]> for List<T> {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let digits = self.len().to_string().len();
+        let mut x = 0;
+        let mut y = 0;
+        for (index, item) in self.iter().enumerate() {
+            if index > 0 {
+                engine.write(b"\n")?;
+            }
+            let marker = if Some(index) == self.selected() { '*' } else { ' ' };
+            let number = format!("{marker} {:>digits$}) ", index + 1);
+            engine.write(number.as_bytes())?;
+            let [w, h] = item.render(engine)?.unwrap_or([0, 1]);
+            x = x.max(number.len() as u16 + w);
+            y += h;
+        }
+        Ok(Some([x, y]))
+    }
+}
+
+/// Entering the number of an item selects it, returning its index.
+impl<R: BufRead, W: Write, T> Input<Repl<R, W>, usize> for List<T> {
+    fn handle (&mut self, engine: &mut Repl<R, W>) -> Result<Option<usize>> {
+        let choice = engine.event.as_deref().and_then(|line|line.trim().parse::<usize>().ok());
+        Ok(match choice {
+            Some(number) if number > 0 && self.select(number - 1) => Some(number - 1),
+            _ => None
+        })
+    }
+}
+
+/// A table is shown as aligned plain text, with its columns sized to the output's width.
+impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for Table {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let widths = self.layout(engine.area.w());
+        let titles: Vec<String> = (0..self.columns.len()).map(|column|self.title(column)).collect();
+        let mut lines = vec![titles];
+        lines.extend(self.iter().cloned());
+        for (index, cells) in lines.iter().enumerate() {
+            if index > 0 {
+                engine.write(b"\n")?;
+            }
+            let mut line = String::new();
+            for (column, width) in widths.iter().enumerate() {
+                if column > 0 {
+                    line.push_str(&" ".repeat(TABLE_GAP as usize));
+                }
+                let cell = ellipsize(cells.get(column).map_or("", |cell|cell.as_str()), *width);
+                let pad = (*width as usize).saturating_sub(UnicodeWidthStr::width(cell.as_str()));
+                line.push_str(&cell);
+                line.push_str(&" ".repeat(pad));
+            }
+            engine.write(line.trim_end().as_bytes())?;
+        }
+        let gaps = TABLE_GAP.saturating_mul(u16::try_from(widths.len()).unwrap_or(u16::MAX).saturating_sub(1));
+        let width = widths.iter().fold(gaps, |width, column|width.saturating_add(*column));
+        Ok(Some([width, u16::try_from(lines.len()).unwrap_or(u16::MAX)]))
+    }
+}
+
+
+/// A tree is shown as its visible rows, with the same indentation guides as in the TUI.
+impl<R: BufRead, W: Write> Output<Repl<R, W>, [u16;2]> for TreeView {
+    fn render (&self, engine: &mut Repl<R, W>) -> Result<Option<[u16;2]>> {
+        let mut width = 0;
+        for (index, path) in self.iter().enumerate() {
+            if index > 0 {
+                engine.write(b"\n")?;
+            }
+            let line = format!("{}{}", self.prefix(path), self.node(path).map_or("", |node|node.label.as_str()));
+            engine.write(line.as_bytes())?;
+            width = width.max(u16::try_from(UnicodeWidthStr::width(line.as_str())).unwrap_or(u16::MAX));
+        }
+        Ok(Some([width, u16::try_from(self.len()).unwrap_or(u16::MAX)]))
     }
 }
@@ -13,8 +13,13 @@ use crate::{
 
 use std::{io::{Write, Error, ErrorKind}};
 
+use unicode_segmentation::UnicodeSegmentation;
+
 impl<W: Write> Output<TUI<W>, [u16;2]> for u16 {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        self.measure(engine)
+    }
+    fn measure (&self, _: &mut TUI<W>) -> Result<Option<[u16;2]>> {
         Ok(Some([*self, *self]))
     }
 }
@@ -23,94 +28,477 @@ impl<W: Write> Output<TUI<W>, [u16;2]> for String {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
         self.as_str().render(engine)
     }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        self.as_str().measure(engine)
+    }
 }
 
+/// Text is drawn on a single line. For wrapping, see `Paragraph`.
 impl<W: Write> Output<TUI<W>, [u16;2]> for &str {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
         engine.put(engine.area.x(), engine.area.y(), &self)?;
-        // FIXME: handle line wrap
-        Ok(Some([self.len() as u16, 1]))
+        self.measure(engine)
+    }
+    fn measure (&self, _: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some([text_width(self), 1]))
     }
 }
 
-impl<W: Write, O: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Fixed<u16, O> {
+impl<W: Write, S: AsRef<str>> Output<TUI<W>, [u16;2]> for Paragraph<S> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
-        self.get().render(engine.area(|area|match self {
-            Self::X(width, _)            => [area.x(), area.y(), *width, area.h()],
-            Self::Y(height, _)           => [area.x(), area.y(), area.w(), *height],
-            Self::XY((width, height), _) => [area.x(), area.y(), *width, *height]
-        }))
+        let [x, y, w, h] = engine.area;
+        for (row, (dx, line)) in self.lines(w, h).iter().enumerate() {
+            engine.put(x + dx, y + row as u16, line)?;
+        }
+        self.measure(engine)
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some(self.size(engine.area.w(), engine.area.h())))
     }
 }
 
-impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Max<u16, T> {
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Styled<T> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
-        self.get().render(engine.area(|area|match self {
-            Self::X(max_width, _) => {
-                [area.x(), area.y(), area.w().max(*max_width), area.h()]
-            },
-            Self::Y(max_height, _) => {
-                [area.x(), area.y(), area.w(), area.h().max(*max_height)]
-            },
-            Self::XY((max_width, max_height), _) => {
-                [area.x(), area.y(), area.w().max(*max_width), area.h().max(*max_height)]
+        engine.with_style(self.0, |engine|self.1.render(engine))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        self.1.measure(engine)
+    }
+}
+
+/// The spans of a line are drawn one after the other, clipped to the area.
+impl<W: Write, T: AsRef<str>> Output<TUI<W>, [u16;2]> for Line<T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, _, _] = engine.area;
+        engine.with_clip(engine.area, |engine| -> Result<()> {
+            let mut dx = 0;
+            for Styled(style, text) in self.0.iter() {
+                engine.with_style(*style, |engine|engine.put(x + dx, y, &text.as_ref()).map(|_|()))?;
+                dx += text_width(text.as_ref());
             }
-        }))
+            Ok(())
+        })?;
+        self.measure(engine)
+    }
+    fn measure (&self, _: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some([self.0.iter().map(|span|text_width(span.1.as_ref())).sum(), 1]))
+    }
+}
+
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Fixed<u16, T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_clipped_area(self.inner(engine.area), |engine|self.get().render(engine))?;
+        Ok(self.outer(size))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().measure(engine))?;
+        Ok(self.outer(size))
+    }
+}
+
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Max<u16, T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_clipped_area(self.inner(engine.area), |engine|self.get().render(engine))?;
+        Ok(self.outer(size))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().measure(engine))?;
+        Ok(self.outer(size))
     }
 }
 
 impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Min<u16, T> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
-        self.get().render(engine.area(|area|match self {
-            Self::X(min_width, _) => [
-                area.x(), area.y(), area.w().min(*min_width), area.h()
-            ],
-            Self::Y(min_height, _) => [
-                area.x(), area.y(), area.w(), area.h().min(*min_height)
-            ],
-            Self::XY((min_width, min_height), _) => [
-                area.x(), area.y(), area.w().min(*min_width), area.h().min(*min_height)
-            ]
-        }))
+        let size = engine.with_clipped_area(self.inner(engine.area), |engine|self.get().render(engine))?;
+        Ok(self.outer(size))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.get().measure(engine))?;
+        Ok(self.outer(size))
     }
 }
 
 impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Offset<u16, T> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
-        self.2.render(engine.area(|area|[
-            area.x() + self.0,
-            area.y() + self.1,
-            area.w().saturating_sub(self.0),
-            area.h().saturating_sub(self.1)
-        ]))
+        let size = engine.with_clipped_area(self.inner(engine.area), |engine|self.2.render(engine))?;
+        Ok(self.outer(size))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let size = engine.with_area(self.inner(engine.area), |engine|self.2.measure(engine))?;
+        Ok(self.outer(size))
+    }
+}
+
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Aligned<T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let area = engine.area;
+        match engine.with_area(area, |engine|self.1.measure(engine))? {
+            Some(size) => engine.with_clipped_area(self.0.place(area, size), |engine|self.1.render(engine))?,
+            None => engine.with_clipped_area(area, |engine|self.1.render(engine))?
+        };
+        Ok(Some(area.size()))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some(engine.area.size()))
     }
 }
 
 impl<'a, W: Write> Output<TUI<W>, [u16;2]> for Rows<'a, TUI<W>, [u16;2]> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
-        let mut x = 0;
-        let mut y = 0;
         expect_min(&engine.area, [1, self.0.len() as u16])?; // FIXME width
-        for item in self.0.iter() {
-            let [w, h] = Offset(0, y, item).render(engine)?.unwrap_or([0, 0]);
-            x = x.max(w);
-            y = y + h;
-        }
-        Ok(Some([x, y]))
+        arrange(&self.0, engine, Axis::Y, self.1.1)
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        measure_along(&self.0, engine, Axis::Y)
     }
 }
 
 impl<'a, W: Write> Output<TUI<W>, [u16;2]> for Columns<'a, TUI<W>, [u16;2]> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
-        let mut x = 0;
-        let mut y = 0;
         expect_min(&engine.area, [self.0.len() as u16, 1])?; // FIXME height
-        for item in self.0.iter() {
-            let [w, h] = Offset(x, 0, item).render(engine)?.unwrap_or([0, 0]);
-            x = x + w;
-            y = y.max(h);
+        arrange(&self.0, engine, Axis::X, self.1.1)
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        measure_along(&self.0, engine, Axis::X)
+    }
+}
+
+/// Length of an area or size along the axis of a container, and across it.
+fn along (axis: Axis, [w, h]: [u16;2]) -> (u16, u16) {
+    match axis { Axis::X => (w, h), _ => (h, w) }
+}
+
+/// Inverse of `along`.
+fn across (axis: Axis, main: u16, cross: u16) -> [u16;2] {
+    match axis { Axis::X => [main, cross], _ => [cross, main] }
+}
+
+/// If any item has a flex policy, divide the engine's area between all items up front.
+fn divide <'a, W: Write> (
+    items: &[Collected<'a, TUI<W>, [u16;2]>], engine: &mut TUI<W>, axis: Axis
+) -> Result<Option<Vec<u16>>> {
+    if items.iter().all(|item|item.flex() == Flex::Auto) {
+        return Ok(None)
+    }
+    let (space, _) = along(axis, [engine.area.w(), engine.area.h()]);
+    let mut bases = Vec::with_capacity(items.len());
+    for item in items.iter() {
+        bases.push((item.flex(), item.measure(engine)?.map(|size|along(axis, size).0)));
+    }
+    Ok(Some(Flex::divide(space, &bases)))
+}
+
+/// Render items one after the other along an axis. Without flex policies,
+/// each item is measured in the remaining space and given only what it needs;
+/// otherwise, each item gets its share of the whole area.
+/// The selected item is kept in view by the enclosing `Scrollable`.
+fn arrange <'a, W: Write> (
+    items: &[Collected<'a, TUI<W>, [u16;2]>], engine: &mut TUI<W>, axis: Axis,
+    selected: Option<usize>
+) -> Result<Option<[u16;2]>> {
+    let area = engine.area;
+    let (space, _) = along(axis, [area.w(), area.h()]);
+    // The part of the area between `offset` and `offset + length` along the axis
+    let slot = |offset: u16, length: u16| match axis {
+        Axis::X => [area.x().saturating_add(offset), area.y(), length, area.h()],
+        _       => [area.x(), area.y().saturating_add(offset), area.w(), length],
+    };
+    let shares = divide(items, engine, axis)?;
+    let mut offset = 0;
+    let mut extent = 0;
+    for (index, item) in items.iter().enumerate() {
+        let free = space.saturating_sub(offset);
+        let (slot, size) = match &shares {
+            Some(shares) => (slot(offset, shares[index].min(free)), None),
+            None => {
+                let size = engine.with_area(slot(offset, free), |engine|item.measure(engine))?;
+                (slot(offset, size.map_or(free, |size|along(axis, size).0.min(free))), size)
+            }
+        };
+        if Some(index) == selected {
+            // Before rendering, so that items within the item can override it
+            engine.reveal(slot);
         }
-        Ok(Some([x, y]))
+        let [w, h] = engine.enter(index).with_clipped_area(slot, |engine|item.render(engine))?
+            .or(size).unwrap_or([0, 0]);
+        let (length, cross) = along(axis, [w, h]);
+        if shares.is_some() {
+            engine.record(slot).leave();
+            offset = offset.saturating_add(along(axis, [slot.w(), slot.h()]).0);
+        } else {
+            engine.record([slot.x(), slot.y(), w, h]).leave();
+            offset = offset.saturating_add(length);
+        }
+        extent = extent.max(cross);
+    }
+    Ok(Some(across(axis, offset, extent)))
+}
+
+/// Measure items placed one after the other along an axis,
+/// without rendering them. `None` if any item's size is unknown.
+fn measure_along <'a, W: Write> (
+    items: &[Collected<'a, TUI<W>, [u16;2]>], engine: &mut TUI<W>, axis: Axis
+) -> Result<Option<[u16;2]>> {
+    let area = engine.area;
+    let (space, _) = along(axis, [area.w(), area.h()]);
+    let shares = divide(items, engine, axis)?;
+    let mut offset: u16 = 0;
+    let mut extent = 0;
+    for (index, item) in items.iter().enumerate() {
+        let free = space.saturating_sub(offset);
+        let rest = match axis {
+            Axis::X => [area.x().saturating_add(offset), area.y(), free, area.h()],
+            _       => [area.x(), area.y().saturating_add(offset), area.w(), free],
+        };
+        let (length, cross) = match engine.with_area(rest, |engine|item.measure(engine))? {
+            Some(size) => along(axis, size),
+            None => return Ok(None)
+        };
+        offset = offset.saturating_add(match &shares {
+            Some(shares) => shares[index].min(free),
+            None => length
+        });
+        extent = extent.max(cross);
+    }
+    Ok(Some(across(axis, offset, extent)))
+}
+
+/// The items of a `FocusList` are rendered as rows, and the selected one is kept in view.
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for FocusList<T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let items: Vec<Collected<TUI<W>, [u16;2]>> = self.iter().map(|item|Collected::Ref(item)).collect();
+        arrange(&items, engine, Axis::Y, self.selected())
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let items: Vec<Collected<TUI<W>, [u16;2]>> = self.iter().map(|item|Collected::Ref(item)).collect();
+        measure_along(&items, engine, Axis::Y)
+    }
+}
+
+/// The visible items of a `List` are drawn as rows, with the selected one highlighted
+/// across the whole width in the theme's focus color.
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for List<T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let mut heights = Vec::with_capacity(self.len());
+        for item in self.iter() {
+            heights.push(engine.with_area(engine.area, |engine|item.measure(engine))?.map_or(1, |[_, h]|h.max(1)));
+        }
+        let offset = self.follow(&heights, h);
+        let highlight = engine.theme.selection();
+        let mut row = 0;
+        let mut visible = 0;
+        for (index, item) in self.iter().enumerate().skip(offset) {
+            if row >= h {
+                break
+            }
+            let slot = [x, y + row, w, heights[index].min(h - row)];
+            engine.enter(index);
+            if Some(index) == self.selected() {
+                engine.reveal(slot);
+                engine.with_clipped_area(slot, |engine|engine.with_style(highlight, |engine| -> Result<()> {
+                    for line in slot[1]..slot[1] + slot[3] {
+                        engine.put(x, line, &" ".repeat(w as usize))?;
+                    }
+                    item.render(engine).map(|_|())
+                }))?;
+            } else {
+                engine.with_clipped_area(slot, |engine|item.render(engine))?;
+            }
+            engine.record(slot).leave();
+            row += heights[index];
+            visible += 1;
+        }
+        self.visible.set(visible);
+        Ok(Some([w, row.min(h)]))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let items: Vec<Collected<TUI<W>, [u16;2]>> = self.iter().map(|item|Collected::Ref(item)).collect();
+        measure_along(&items, engine, Axis::Y)
+    }
+}
+
+/// Up, Down, PageUp, PageDown, Home and End, the mouse wheel, or a click on an item
+/// change the selection. If it changed, the index of the selected item is returned.
+impl<W: Write, T> Input<TUI<W>, usize> for List<T> {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<usize>> {
+        let before = self.selected();
+        match &engine.event {
+            Some(TUIInputEvent::Key(KeyEvent { code, .. })) => match code {
+                KeyCode::Up       => { self.select_prev(); },
+                KeyCode::Down     => { self.select_next(); },
+                KeyCode::PageUp   => { self.page_up(); },
+                KeyCode::PageDown => { self.page_down(); },
+                KeyCode::Home     => { self.select_first(); },
+                KeyCode::End      => { self.select_last(); },
+                _ => return Ok(None)
+            },
+            Some(TUIInputEvent::Mouse(MouseEvent { kind, column, row, .. })) => match kind {
+                MouseEventKind::ScrollUp   => { self.select_prev(); },
+                MouseEventKind::ScrollDown => { self.select_next(); },
+                MouseEventKind::Down(MouseButton::Left) => {
+                    let (column, row) = (*column, *row);
+                    let first = self.offset.get();
+                    let count = self.visible.get();
+                    for index in first..first + count {
+                        let hit = engine.enter(index).hit(column, row).is_some();
+                        engine.leave();
+                        if hit {
+                            self.select(index);
+                            break
+                        }
+                    }
+                },
+                _ => return Ok(None)
+            },
+            _ => return Ok(None)
+        }
+        Ok(if self.selected() != before { self.selected() } else { None })
+    }
+}
+
+/// A table is drawn as a bold header row, followed by the visible rows,
+/// with the selected one highlighted like in a `List`.
+impl<W: Write> Output<TUI<W>, [u16;2]> for Table {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let widths = self.layout(w);
+        let body = h.saturating_sub(1);
+        let offset = self.rows.follow_rows(body);
+        let highlight = engine.theme.selection();
+        engine.with_clipped_area([x, y, w, h], |engine| -> Result<()> {
+            let cells = |engine: &mut TUI<W>, y: u16, cell: &dyn Fn(usize)->String| -> Result<()> {
+                let mut cx = x;
+                for (column, width) in widths.iter().enumerate() {
+                    engine.put(cx, y, &ellipsize(&cell(column), *width))?;
+                    cx += width + TABLE_GAP;
+                }
+                Ok(())
+            };
+            engine.with_style(Style::new().bold(), |engine|cells(engine, y, &|column|self.title(column)))?;
+            let rows = self.iter().enumerate().skip(offset).take(body as usize);
+            for (row, (index, cells_of_row)) in rows.enumerate() {
+                let ry = y + 1 + row as u16;
+                let cell = |column: usize|cells_of_row.get(column).cloned().unwrap_or_default();
+                if Some(index) == self.selected() {
+                    engine.reveal([x, ry, w, 1]);
+                    engine.with_style(highlight, |engine| -> Result<()> {
+                        engine.put(x, ry, &" ".repeat(w as usize))?;
+                        cells(engine, ry, &cell)
+                    })?;
+                } else {
+                    cells(engine, ry, &cell)?;
+                }
+            }
+            Ok(())
+        })?;
+        let visible = self.len().saturating_sub(offset).min(body as usize);
+        self.rows.visible.set(visible);
+        Ok(Some([w, 1 + visible as u16]))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let rows = u16::try_from(self.len()).unwrap_or(u16::MAX).saturating_add(1);
+        Ok(Some([engine.area.w(), rows.min(engine.area.h())]))
+    }
+}
+
+/// Rows are selected like in a `List`, or by clicking them. Clicking a column's title,
+/// or pressing its number, sorts the rows by it. If the index of the selected row
+/// changed, it is returned.
+impl<W: Write> Input<TUI<W>, usize> for Table {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<usize>> {
+        let before = self.selected();
+        if let Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Char(c), .. })) = engine.event {
+            match c.to_digit(10).filter(|digit|*digit > 0) {
+                Some(column) => self.sort_by(column as usize - 1),
+                None => return Ok(None)
+            }
+        } else if let Some([mx, my]) = engine.click([engine.area.w(), 1 + self.rows.visible.get() as u16]) {
+            match my {
+                0 => match self.column_at(mx) {
+                    Some(column) => self.sort_by(column),
+                    None => return Ok(None)
+                },
+                _ => { self.select(self.rows.offset.get() + my as usize - 1); }
+            }
+        } else {
+            return self.rows.handle(engine)
+        }
+        engine.request_redraw();
+        Ok(if self.selected() != before { self.selected() } else { None })
+    }
+}
+
+/// A tree is drawn as its visible rows, with the indentation guides in the theme's
+/// border color, and the label of the selected node highlighted.
+impl<W: Write> Output<TUI<W>, [u16;2]> for TreeView {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let offset = self.rows.follow_rows(h);
+        let guides = Style { fg: engine.theme.border_hi, ..Style::default() };
+        let highlight = engine.theme.selection();
+        let mut width = 0;
+        engine.with_clipped_area([x, y, w, h], |engine| -> Result<()> {
+            for (row, (index, path)) in self.iter().enumerate().skip(offset).take(h as usize).enumerate() {
+                let ry = y + row as u16;
+                let prefix = self.prefix(path);
+                let indent = text_width(&prefix);
+                let label = self.node(path).map_or("", |node|node.label.as_str());
+                engine.with_style(guides, |engine|engine.put(x, ry, &prefix).map(|_|()))?;
+                if Some(index) == self.selected() {
+                    engine.reveal([x, ry, w, 1]);
+                    engine.with_style(highlight, |engine|engine.put(x + indent, ry, &label).map(|_|()))?;
+                } else {
+                    engine.put(x + indent, ry, &label)?;
+                }
+                width = width.max(indent + text_width(label));
+            }
+            Ok(())
+        })?;
+        let visible = self.len().saturating_sub(offset).min(h as usize);
+        self.rows.visible.set(visible);
+        Ok(Some([width.min(w), visible as u16]))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let width = self.iter().map(|path|
+            text_width(&self.prefix(path)) + self.node(path).map_or(0, |node|text_width(&node.label))
+        ).max().unwrap_or(0);
+        let rows = u16::try_from(self.len()).unwrap_or(u16::MAX);
+        Ok(Some([width.min(engine.area.w()), rows.min(engine.area.h())]))
+    }
+}
+
+/// Up, Down, PageUp, PageDown, Home and End select nodes like in a `List`.
+/// Right expands the selected node or goes to its first child, Left collapses it
+/// or goes to its parent, and Enter, Space or a click on the marker toggle it.
+/// If the selection changed, the path to the selected node is returned.
+impl<W: Write> Input<TUI<W>, Vec<usize>> for TreeView {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<Vec<usize>>> {
+        let before = self.selected_path();
+        let click = engine.click([engine.area.w(), self.rows.visible.get() as u16]);
+        let changed = match (&engine.event, click) {
+            (_, Some([mx, my])) => {
+                let index = self.rows.offset.get() + my as usize;
+                let marker = self.items().get(index).map_or(0, |path|text_width(&self.prefix(path)));
+                let selected = self.select(index);
+                selected && (mx >= marker || self.toggle())
+            },
+            (Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Left, .. })), _) =>
+                self.collapse(),
+            (Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Right, .. })), _) =>
+                self.expand(),
+            (Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Enter | KeyCode::Char(' '), .. })), _) =>
+                self.toggle(),
+            _ => self.rows.handle(engine)?.is_some()
+        };
+        let after = self.selected_path();
+        if after != before {
+            return Ok(after)
+        }
+        if changed {
+            engine.request_redraw();
+        }
+        Ok(None)
     }
 }
 
@@ -118,14 +506,363 @@ impl<'a, W: Write> Output<TUI<W>, [u16;2]> for Layers<'a, TUI<W>, [u16;2]> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
         let mut x = 0;
         let mut y = 0;
-        expect_min(&engine.area, [1, 1 as u16])?; // FIXME size
-        for item in self.0.iter().rev() {
-            let [w, h] = item.render(engine)?.unwrap_or([0, 0]);
+        let area = engine.area;
+        expect_min(&area, [1, 1 as u16])?; // FIXME size
+        for (index, item) in self.0.iter().enumerate().rev() {
+            let [w, h] = engine.enter(index).with_clipped_area(area, |engine|item.render(engine))?
+                .unwrap_or([0, 0]);
+            engine.record([area.x(), area.y(), w, h]).leave();
             x = x.max(w);
             y = y.max(h);
         }
         Ok(Some([x, y]))
     }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let mut x = 0;
+        let mut y = 0;
+        let area = engine.area;
+        for item in self.0.iter() {
+            match engine.with_area(area, |engine|item.measure(engine))? {
+                Some([w, h]) => { x = x.max(w); y = y.max(h); },
+                None => return Ok(None)
+            }
+        }
+        Ok(Some([x, y]))
+    }
+}
+
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Scrollable<T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        if w == 0 || h == 0 {
+            return Ok(Some([0, 0]))
+        }
+        // Measure the content across the width, with unlimited height
+        let tall = [x, y, w, u16::MAX - y];
+        let [cw, ch] = engine.with_area(tall, |engine|self.content.measure(engine))?.unwrap_or([w, h]);
+        // Make room for the scrollbars that are needed
+        let mut bar_y = self.scrollbars && ch > h;
+        let bar_x = self.scrollbars && cw > w - bar_y as u16;
+        bar_y = self.scrollbars && (bar_y || ch > h - bar_x as u16);
+        let viewport = [x, y, w - bar_y as u16, h - bar_x as u16];
+        let content = [
+            x, y, cw.max(viewport.w()).min(u16::MAX - x), ch.max(viewport.h()).min(u16::MAX - y)
+        ];
+        self.fit(content.size(), viewport.size());
+        // Render the visible part of the content
+        let outer = engine.take_revealed();
+        let render = |engine: &mut TUI<W>, offset| engine.with_area(content, |engine|
+            engine.with_viewport(viewport, offset, |engine|self.content.render(engine))
+        );
+        render(engine, self.offset.get())?;
+        // If the content asked for something else to be kept in view, scroll to it and redraw
+        let revealed = engine.take_revealed();
+        if let Some([rx, ry, rw, rh]) = revealed {
+            if self.reveal([rx.saturating_sub(x), ry.saturating_sub(y), rw, rh]) {
+                engine.blank(viewport);
+                render(engine, self.offset.get())?;
+                engine.take_revealed();
+            }
+        }
+        let offset = self.offset.get();
+        // If something inside wants to be in view, so does the viewport
+        if let Some(area) = revealed.map(|_|viewport).or(outer) {
+            engine.reveal(area);
+        }
+        // Draw the scrollbars
+        let [ox, oy] = offset;
+        let [max_x, max_y] = self.max_offset();
+        if bar_y {
+            let (start, length) = thumb(viewport.h(), ch, oy, max_y);
+            for row in 0..viewport.h() {
+                let thumb = row >= start && row < start + length;
+                engine.put(x + w - 1, y + row, &if thumb { '█' } else { '│' })?;
+            }
+        }
+        if bar_x {
+            let (start, length) = thumb(viewport.w(), cw, ox, max_x);
+            let bar: String = (0..viewport.w())
+                .map(|column|if column >= start && column < start + length { '█' } else { '─' })
+                .collect();
+            engine.put(x, y + h - 1, &bar)?;
+        }
+        Ok(Some([w, h]))
+    }
+    /// A scrollable is no larger than its content.
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let tall = [x, y, w, u16::MAX - y];
+        Ok(engine.with_area(tall, |engine|self.content.measure(engine))?
+            .map(|[cw, ch]|[cw.min(w), ch.min(h)]))
+    }
+}
+
+/// Position and length of a scrollbar thumb, given the lengths of the track and the content.
+fn thumb (track: u16, content: u16, offset: u16, max_offset: u16) -> (u16, u16) {
+    if track == 0 {
+        return (0, 0)
+    }
+    let length = ((track as u32 * track as u32) / content.max(1) as u32).clamp(1, track as u32) as u16;
+    let start = if max_offset == 0 {
+        0
+    } else {
+        ((track - length) as u32 * offset as u32 / max_offset as u32) as u16
+    };
+    (start, length)
+}
+
+/// Input is passed to the content first. If it isn't handled there,
+/// PageUp/PageDown/Home/End and the mouse wheel (with Shift for horizontal) scroll the viewport.
+impl<W: Write, U, V: Input<TUI<W>, U>> Input<TUI<W>, U> for Scrollable<V> {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<U>> {
+        if let Some(message) = self.content.handle(engine)? {
+            return Ok(Some(message))
+        }
+        let [ox, _] = self.offset.get();
+        let [x, y, w, h] = engine.area;
+        let scrolled = match &engine.event {
+            Some(TUIInputEvent::Key(KeyEvent { code, .. })) => match code {
+                KeyCode::PageUp   => self.scroll_pages(-1),
+                KeyCode::PageDown => self.scroll_pages(1),
+                KeyCode::Home     => self.scroll_to([ox, 0]),
+                KeyCode::End      => self.scroll_to([ox, self.max_offset()[1]]),
+                _ => false
+            },
+            Some(TUIInputEvent::Mouse(MouseEvent { kind, column, row, modifiers }))
+                if *column >= x && *column < x + w && *row >= y && *row < y + h =>
+            {
+                let horizontal = modifiers.contains(KeyModifiers::SHIFT);
+                match (kind, horizontal) {
+                    (MouseEventKind::ScrollUp,   false) => self.scroll_by(0, -3),
+                    (MouseEventKind::ScrollDown, false) => self.scroll_by(0, 3),
+                    (MouseEventKind::ScrollUp,   true)  => self.scroll_by(-3, 0),
+                    (MouseEventKind::ScrollDown, true)  => self.scroll_by(3, 0),
+                    _ => false
+                }
+            },
+            _ => false
+        };
+        if scrolled {
+            engine.request_redraw();
+        }
+        Ok(None)
+    }
+}
+
+/// A toggle is drawn as its glyph, highlighted when on, followed by its label.
+impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Toggle<T> {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let glyph = self.glyph();
+        let indent = text_width(glyph) + 1;
+        let color = if self.0 { engine.theme.accent } else { engine.theme.muted };
+        engine.set_colors(&color, &None)?.put(x, y, &glyph)?.set_colors(&None, &None)?;
+        let label = [x + indent, y, w.saturating_sub(indent), h];
+        let [lw, lh] = engine.with_clipped_area(label, |engine|self.1.render(engine))?.unwrap_or([0, 1]);
+        Ok(Some([indent + lw, lh.max(1)]))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let indent = text_width(self.glyph()) + 1;
+        let label = [x + indent, y, w.saturating_sub(indent), h];
+        Ok(engine.with_area(label, |engine|self.1.measure(engine))?
+            .map(|[lw, lh]|[indent + lw, lh.max(1)]))
+    }
+}
+
+/// Space, Enter, or a left click on the toggle flip it, returning the new value.
+impl<W: Write, T> Input<TUI<W>, bool> for Toggle<T> {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<bool>> {
+        let [x, y, w, h] = engine.area;
+        let toggled = match &engine.event {
+            Some(TUIInputEvent::Key(KeyEvent { code: KeyCode::Char(' ') | KeyCode::Enter, .. })) =>
+                Some(self.toggle()),
+            Some(TUIInputEvent::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left), column, row, ..
+            })) if *column >= x && *column < x + w && *row >= y && *row < y + h =>
+                Some(self.toggle()),
+            _ => None
+        };
+        if toggled.is_some() {
+            engine.request_redraw();
+        }
+        Ok(toggled)
+    }
+}
+
+/// A text field is drawn as the visible part of its value, with the selection
+/// and the cursor in reverse video, or as its placeholder, dimmed, when empty.
+impl<W: Write> Output<TUI<W>, [u16;2]> for TextInput {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, _] = engine.area;
+        let scroll = self.fit(w);
+        let cursor = self.cursor_column();
+        let selection = self.selection().unwrap_or(0..0);
+        engine.with_clipped_area([x, y, w, 1], |engine| -> Result<()> {
+            if self.value.is_empty() {
+                engine.with_style(Style::new().dim(), |engine|engine.put(x, y, &self.placeholder).map(|_|()))?;
+            }
+            let mut column = 0;
+            for (index, grapheme) in self.value.grapheme_indices(true) {
+                let width = text_width(grapheme);
+                if column >= scroll {
+                    let style = if selection.contains(&index) { Style::new().reverse() } else { Style::new() };
+                    engine.with_style(style, |engine|engine.put(x + column - scroll, y, &grapheme).map(|_|()))?;
+                }
+                column += width;
+            }
+            let under = self.value[self.cursor..].graphemes(true).next()
+                .or_else(||if self.value.is_empty() { self.placeholder.graphemes(true).next() } else { None })
+                .unwrap_or(" ");
+            engine.with_style(Style::new().reverse(), |engine|engine.put(x + cursor - scroll, y, &under).map(|_|()))?;
+            Ok(())
+        })?;
+        Ok(Some([w, 1]))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some([engine.area[2], 1]))
+    }
+}
+
+/// Keys edit the value, with Shift extending the selection and Ctrl moving by words.
+/// Up and Down browse the history. Edits return `Change`, and Enter returns `Submit`.
+impl<W: Write> Input<TUI<W>, TextInputMessage> for TextInput {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<TextInputMessage>> {
+        let before = (self.value.clone(), self.cursor, self.anchor);
+        match &engine.event {
+            Some(TUIInputEvent::Key(KeyEvent { code, modifiers, .. })) => {
+                let select = modifiers.contains(KeyModifiers::SHIFT);
+                let word   = modifiers.contains(KeyModifiers::CONTROL);
+                match code {
+                    KeyCode::Enter => {
+                        engine.request_redraw();
+                        return Ok(Some(TextInputMessage::Submit(self.submit())))
+                    },
+                    KeyCode::Char('a') if word  => self.select_all(),
+                    KeyCode::Char('w') if word  => { self.delete_word(); },
+                    KeyCode::Char(c) if !word   => self.insert(c.encode_utf8(&mut [0; 4])),
+                    KeyCode::Backspace if word  => { self.delete_word(); },
+                    KeyCode::Backspace          => { self.backspace(); },
+                    KeyCode::Delete             => { self.delete(); },
+                    KeyCode::Left  if word      => self.word_left(select),
+                    KeyCode::Right if word      => self.word_right(select),
+                    KeyCode::Left               => self.left(select),
+                    KeyCode::Right              => self.right(select),
+                    KeyCode::Home               => self.home(select),
+                    KeyCode::End                => self.end(select),
+                    KeyCode::Up                 => { self.history_prev(); },
+                    KeyCode::Down               => { self.history_next(); },
+                    _ => return Ok(None)
+                }
+            },
+            Some(TUIInputEvent::Paste(text)) => self.insert(text),
+            _ => return Ok(None)
+        }
+        if before != (self.value.clone(), self.cursor, self.anchor) {
+            engine.request_redraw();
+        }
+        if before.0 != self.value {
+            return Ok(Some(TextInputMessage::Change(self.value.clone())))
+        }
+        Ok(None)
+    }
+}
+
+/// A text area is drawn as its visible rows, with the selection and the cursor in reverse video.
+impl<W: Write> Output<TUI<W>, [u16;2]> for TextArea {
+    fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        let [x, y, w, h] = engine.area;
+        let scroll = self.fit([w, h]);
+        let rows = self.rows(w);
+        let [cursor_x, cursor_y] = self.locate(&rows, self.cursor);
+        let selection = self.selection().unwrap_or(0..0);
+        engine.with_clipped_area([x, y, w, h], |engine| -> Result<()> {
+            for (row, range) in rows.iter().enumerate().skip(scroll as usize).take(h as usize) {
+                let row = y + row as u16 - scroll;
+                let mut column = 0;
+                for (index, grapheme) in self.value[range.clone()].grapheme_indices(true) {
+                    let style = if selection.contains(&(range.start + index)) { Style::new().reverse() } else { Style::new() };
+                    engine.with_style(style, |engine|engine.put(x + column, row, &grapheme).map(|_|()))?;
+                    column += text_width(grapheme);
+                }
+            }
+            let mut under = self.value[self.cursor..].graphemes(true).next()
+                .filter(|grapheme|*grapheme != "\n").unwrap_or(" ");
+            let mut cursor_x = cursor_x;
+            if cursor_x >= w {
+                // Past the end of a full row, the cursor is shown on its last visible character
+                let mut column = 0;
+                (cursor_x, under) = (w.saturating_sub(1), " ");
+                for grapheme in self.value[rows[cursor_y as usize].clone()].graphemes(true) {
+                    let width = text_width(grapheme);
+                    if column + width > w {
+                        break
+                    }
+                    (cursor_x, under) = (column, grapheme);
+                    column += width;
+                }
+            }
+            if cursor_y >= scroll {
+                let [cx, cy] = [x + cursor_x, y + cursor_y - scroll];
+                engine.with_style(Style::new().reverse(), |engine|engine.put(cx, cy, &under).map(|_|()))?;
+            }
+            Ok(())
+        })?;
+        Ok(Some([w, h]))
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some(engine.area.size()))
+    }
+}
+
+/// Keys edit the text, with Shift extending the selection. Ctrl+C, Ctrl+X and Ctrl+V
+/// use the engine's clipboard, and Ctrl+Z and Ctrl+Y undo and redo. Edits return `Change`.
+impl<W: Write> Input<TUI<W>, TextInputMessage> for TextArea {
+    fn handle (&mut self, engine: &mut TUI<W>) -> Result<Option<TextInputMessage>> {
+        let before = (self.value.clone(), self.cursor, self.anchor);
+        match &engine.event {
+            Some(TUIInputEvent::Key(KeyEvent { code, modifiers, .. })) => {
+                let select  = modifiers.contains(KeyModifiers::SHIFT);
+                let control = modifiers.contains(KeyModifiers::CONTROL);
+                match code {
+                    KeyCode::Char('a') if control => self.select_all(),
+                    KeyCode::Char('c') if control => if self.selection().is_some() {
+                        engine.clipboard = self.selected().to_string()
+                    },
+                    KeyCode::Char('x') if control => if self.selection().is_some() {
+                        engine.clipboard = self.cut()
+                    },
+                    KeyCode::Char('v') if control => self.insert(&engine.clipboard.clone()),
+                    KeyCode::Char('z') if control => { self.undo(); },
+                    KeyCode::Char('y') if control => { self.redo(); },
+                    KeyCode::Char(c) if !control  => self.insert(c.encode_utf8(&mut [0; 4])),
+                    KeyCode::Enter                => self.insert("\n"),
+                    KeyCode::Backspace            => { self.backspace(); },
+                    KeyCode::Delete               => { self.delete(); },
+                    KeyCode::Left                 => self.left(select),
+                    KeyCode::Right                => self.right(select),
+                    KeyCode::Up                   => self.up(1, select),
+                    KeyCode::Down                 => self.down(1, select),
+                    KeyCode::PageUp               => self.page_up(select),
+                    KeyCode::PageDown             => self.page_down(select),
+                    KeyCode::Home if control      => self.start_of_text(select),
+                    KeyCode::End  if control      => self.end_of_text(select),
+                    KeyCode::Home                 => self.home(select),
+                    KeyCode::End                  => self.end(select),
+                    _ => return Ok(None)
+                }
+            },
+            Some(TUIInputEvent::Paste(text)) => self.insert(text),
+            _ => return Ok(None)
+        }
+        if before != (self.value.clone(), self.cursor, self.anchor) {
+            engine.request_redraw();
+        }
+        if before.0 != self.value {
+            return Ok(Some(TextInputMessage::Change(self.value.clone())))
+        }
+        Ok(None)
+    }
 }
 
 /// Return an error if the available area is larger than the minimum needed size
@@ -186,98 +923,122 @@ impl<W, X, Y, Z> Output<TUI<W>, [u16;2]> for Border<W, X, Y, Z> where
 
         let w = engine.area.w();
         let h = engine.area.h();
-        if w == 0 || h == 0 {
+        if w < 2 || h < 2 {
             return Ok(None)
         }
 
-        let x = engine.area.x();
-        let y = engine.area.y();
-
-        // Draw top
-        let (top_left, fg, bg) = Y::top_left(&self.theme);
-        engine.set_colors(&fg, &bg)?.put(x, y, &top_left)?;
-        let (top, fg, bg) = Y::top(&self.theme);
-        engine.set_colors(&fg, &bg)?.put(x+1, y, &String::from(top).repeat((w-2) as usize))?;
-        let (top_right, fg, bg) = Y::top_right(&self.theme);
-        engine.set_colors(&fg, &bg)?.put(x+w-1, y, &top_right)?;
-
-        // Draw sides and background
-        let (left, fg, bg) = Y::left(&self.theme);
-        for y in y+1..y+h-1 {
-            engine.set_colors(&fg, &bg)?.put(x, y, &left)?;
-        }
+        let colors = self.theme.colors(&engine.theme);
 
-        engine.set_colors(&self.theme.hi(), &self.theme.bg())?;
-        for y in y+1..y+h-1 {
-            engine.put(x+1, y, &" ".repeat((w-2) as usize))?;
-        }
+        // Don't draw outside of the border's own area
+        engine.with_clip(engine.area, |engine|{
 
-        let (right, fg, bg) = Y::right(&self.theme);
-        engine.set_colors(&fg, &bg)?;
-        for y in y+1..y+h-1 {
-            engine.put(x+w-1, y, &right)?;
-        }
+            let x = engine.area.x();
+            let y = engine.area.y();
+
+            // Draw top
+            let (top_left, fg, bg) = Y::top_left(&colors);
+            engine.set_colors(&fg, &bg)?.put(x, y, &top_left)?;
+            let (top, fg, bg) = Y::top(&colors);
+            engine.set_colors(&fg, &bg)?.put(x+1, y, &String::from(top).repeat((w-2) as usize))?;
+            let (top_right, fg, bg) = Y::top_right(&colors);
+            engine.set_colors(&fg, &bg)?.put(x+w-1, y, &top_right)?;
+
+            // Draw sides and background
+            let (left, fg, bg) = Y::left(&colors);
+            for y in y+1..y+h-1 {
+                engine.set_colors(&fg, &bg)?.put(x, y, &left)?;
+            }
+
+            engine.set_colors(&colors.hi, &colors.bg)?;
+            for y in y+1..y+h-1 {
+                engine.put(x+1, y, &" ".repeat((w-2) as usize))?;
+            }
 
-        // Draw bottom
-        let (bottom_left, fg, bg) = Y::bottom_left(&self.theme);
-        engine.set_colors(&fg, &bg)?.put(x, y+h-1, &bottom_left)?;
-        let (bottom, fg, bg) = Y::bottom(&self.theme);
-        engine.set_colors(&fg, &bg)?.put(x+1, y+h-1, &String::from(bottom).repeat((w-2) as usize))?;
-        let (bottom_right, fg, bg) = Y::bottom_right(&self.theme);
-        engine.set_colors(&fg, &bg)?.put(x+w-1, y+h-1, &bottom_right)?;
+            let (right, fg, bg) = Y::right(&colors);
+            engine.set_colors(&fg, &bg)?;
+            for y in y+1..y+h-1 {
+                engine.put(x+w-1, y, &right)?;
+            }
+
+            // Draw bottom
+            let (bottom_left, fg, bg) = Y::bottom_left(&colors);
+            engine.set_colors(&fg, &bg)?.put(x, y+h-1, &bottom_left)?;
+            let (bottom, fg, bg) = Y::bottom(&colors);
+            engine.set_colors(&fg, &bg)?.put(x+1, y+h-1, &String::from(bottom).repeat((w-2) as usize))?;
+            let (bottom_right, fg, bg) = Y::bottom_right(&colors);
+            engine.set_colors(&fg, &bg)?.put(x+w-1, y+h-1, &bottom_right)?;
+
+            // Set background color
+            engine.set_colors(&None, &colors.bg)?;
 
-        // Set background color
-        engine.set_colors(&None, &self.theme.bg())?;
+            // Shrink area by border size and draw contained element
+            engine.with_clipped_area([x+1,y+1,w-2,h-2], |engine|self.widget.render(engine))
 
-        // Grow area by border size
-        engine.area = [x+1,y+1,w-2,h-2];
+        })?;
 
-        // Draw contained element
-        self.widget.render(engine)
+        // The border fills the whole area
+        Ok(Some([w, h]))
+    }
+
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        Ok(Some(engine.area.size()))
     }
 
 }
 
 
-/// A set of colors to use for rendering a border.
+/// A set of colors to use for rendering a border, picked from the engine's theme.
 pub trait BorderTheme {
     /// The color outside the box
-    fn out (&self) -> Option<Color> { None }
+    fn out (&self, _theme: &Theme) -> Option<Color> { None }
     /// The background of the box
-    fn bg  (&self) -> Option<Color> { None }
+    fn bg  (&self, _theme: &Theme) -> Option<Color> { None }
     /// One border color.
-    fn hi  (&self) -> Option<Color>;
+    fn hi  (&self, theme: &Theme) -> Option<Color>;
     /// The other border color.
-    fn lo  (&self) -> Option<Color>;
+    fn lo  (&self, theme: &Theme) -> Option<Color>;
+    /// Pick all the colors from the theme.
+    fn colors (&self, theme: &Theme) -> BorderColors {
+        BorderColors { out: self.out(theme), bg: self.bg(theme), hi: self.hi(theme), lo: self.lo(theme) }
+    }
 }
 
-/// Colors for an inset grey border.
+/// The colors of a border, as picked from a theme.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct BorderColors {
+    pub out: Option<Color>,
+    pub bg:  Option<Color>,
+    pub hi:  Option<Color>,
+    pub lo:  Option<Color>,
+}
+
+/// Colors for an inset border.
 pub struct Inset;
 
 impl BorderTheme for Inset {
-    fn bg (&self) -> Option<Color> {
-        Some(Color::AnsiValue(235))
+    fn bg (&self, theme: &Theme) -> Option<Color> {
+        theme.background
     }
-    fn hi (&self) -> Option<Color> {
-        Some(Color::AnsiValue(240))
+    fn hi (&self, theme: &Theme) -> Option<Color> {
+        theme.border_hi
     }
-    fn lo (&self) -> Option<Color> {
-        Some(Color::AnsiValue(16))
+    fn lo (&self, theme: &Theme) -> Option<Color> {
+        theme.border_lo
     }
 }
 
-/// Colors for an outset grey border.
+/// Colors for an outset border.
 pub struct Outset;
 
 impl BorderTheme for Outset {
-    fn bg (&self) -> Option<Color> {
-        Some(Color::AnsiValue(235))
+    fn bg (&self, theme: &Theme) -> Option<Color> {
+        theme.background
     }
-    fn hi (&self) -> Option<Color> {
-        Some(Color::AnsiValue(16))
+    fn hi (&self, theme: &Theme) -> Option<Color> {
+        theme.border_lo
     }
-    fn lo (&self) -> Option<Color> {
-        Some(Color::AnsiValue(240))
+    fn lo (&self, theme: &Theme) -> Option<Color> {
+        theme.border_hi
     }
 }
 
@@ -286,43 +1047,43 @@ pub type BorderChar = (char, Option<Color>, Option<Color>);
 
 /// A set of characters to use for rendering a border.
 pub trait BorderStyle {
-    fn top (theme: &impl BorderTheme) -> BorderChar;
-    fn top_left (theme: &impl BorderTheme) -> BorderChar;
-    fn top_right (theme: &impl BorderTheme) -> BorderChar;
-    fn left (theme: &impl BorderTheme) -> BorderChar;
-    fn right (theme: &impl BorderTheme) -> BorderChar;
-    fn bottom (theme: &impl BorderTheme) -> BorderChar;
-    fn bottom_left (theme: &impl BorderTheme) -> BorderChar;
-    fn bottom_right (theme: &impl BorderTheme) -> BorderChar;
+    fn top (colors: &BorderColors) -> BorderChar;
+    fn top_left (colors: &BorderColors) -> BorderChar;
+    fn top_right (colors: &BorderColors) -> BorderChar;
+    fn left (colors: &BorderColors) -> BorderChar;
+    fn right (colors: &BorderColors) -> BorderChar;
+    fn bottom (colors: &BorderColors) -> BorderChar;
+    fn bottom_left (colors: &BorderColors) -> BorderChar;
+    fn bottom_right (colors: &BorderColors) -> BorderChar;
 }
 
 /// A border with more vertical space.
 pub struct Tall;
 
 impl BorderStyle for Tall {
-    fn top (theme: &impl BorderTheme) -> BorderChar {
-        ('▇', theme.bg(), theme.lo())
+    fn top (colors: &BorderColors) -> BorderChar {
+        ('▇', colors.bg, colors.lo)
     }
-    fn top_left (theme: &impl BorderTheme) -> BorderChar {
-        ('▊', theme.bg(), theme.lo())
+    fn top_left (colors: &BorderColors) -> BorderChar {
+        ('▊', colors.bg, colors.lo)
     }
-    fn top_right (theme: &impl BorderTheme) -> BorderChar {
-        ('▎', theme.hi(), theme.bg())
+    fn top_right (colors: &BorderColors) -> BorderChar {
+        ('▎', colors.hi, colors.bg)
     }
-    fn left (theme: &impl BorderTheme) -> BorderChar {
-        ('▊', theme.bg(), theme.lo())
+    fn left (colors: &BorderColors) -> BorderChar {
+        ('▊', colors.bg, colors.lo)
     }
-    fn right (theme: &impl BorderTheme) -> BorderChar {
-        ('▎', theme.hi(), theme.bg())
+    fn right (colors: &BorderColors) -> BorderChar {
+        ('▎', colors.hi, colors.bg)
     }
-    fn bottom (theme: &impl BorderTheme) -> BorderChar {
-        ('▁', theme.hi(), theme.bg())
+    fn bottom (colors: &BorderColors) -> BorderChar {
+        ('▁', colors.hi, colors.bg)
     }
-    fn bottom_left (theme: &impl BorderTheme) -> BorderChar {
-        ('▊', theme.bg(), theme.lo())
+    fn bottom_left (colors: &BorderColors) -> BorderChar {
+        ('▊', colors.bg, colors.lo)
     }
-    fn bottom_right (theme: &impl BorderTheme) -> BorderChar {
-        ('▎', theme.hi(), theme.bg())
+    fn bottom_right (colors: &BorderColors) -> BorderChar {
+        ('▎', colors.hi, colors.bg)
     }
 }
 
@@ -330,29 +1091,29 @@ impl BorderStyle for Tall {
 pub struct Wide;
 
 impl BorderStyle for Wide {
-    fn top (theme: &impl BorderTheme) -> BorderChar {
-        ('▁', theme.lo(), theme.bg())
+    fn top (colors: &BorderColors) -> BorderChar {
+        ('▁', colors.lo, colors.bg)
     }
-    fn top_left (theme: &impl BorderTheme) -> BorderChar {
-        ('▁', theme.lo(), theme.bg())
+    fn top_left (colors: &BorderColors) -> BorderChar {
+        ('▁', colors.lo, colors.bg)
     }
-    fn top_right (theme: &impl BorderTheme) -> BorderChar {
-        ('▁', theme.lo(), theme.bg())
+    fn top_right (colors: &BorderColors) -> BorderChar {
+        ('▁', colors.lo, colors.bg)
     }
-    fn left (theme: &impl BorderTheme) -> BorderChar {
-        ('▎', theme.lo(), theme.bg())
+    fn left (colors: &BorderColors) -> BorderChar {
+        ('▎', colors.lo, colors.bg)
     }
-    fn right (theme: &impl BorderTheme) -> BorderChar {
-        ('▊', theme.bg(), theme.hi())
+    fn right (colors: &BorderColors) -> BorderChar {
+        ('▊', colors.bg, colors.hi)
     }
-    fn bottom (theme: &impl BorderTheme) -> BorderChar {
-        ('▇', theme.bg(), theme.hi())
+    fn bottom (colors: &BorderColors) -> BorderChar {
+        ('▇', colors.bg, colors.hi)
     }
-    fn bottom_left (theme: &impl BorderTheme) -> BorderChar {
-        ('▇', theme.bg(), theme.hi())
+    fn bottom_left (colors: &BorderColors) -> BorderChar {
+        ('▇', colors.bg, colors.hi)
     }
-    fn bottom_right (theme: &impl BorderTheme) -> BorderChar {
-        ('▇', theme.bg(), theme.hi())
+    fn bottom_right (colors: &BorderColors) -> BorderChar {
+        ('▇', colors.bg, colors.hi)
     }
 }
 
@@ -360,36 +1121,46 @@ impl BorderStyle for Wide {
 pub struct Flat;
 
 impl BorderStyle for Flat {
-    fn top (theme: &impl BorderTheme) -> BorderChar {
-        ('─', theme.hi(), theme.bg())
+    fn top (colors: &BorderColors) -> BorderChar {
+        ('─', colors.hi, colors.bg)
+    }
+    fn top_left (colors: &BorderColors) -> BorderChar {
+        ('┌', colors.hi, colors.bg)
     }
-    fn top_left (theme: &impl BorderTheme) -> BorderChar {
-        ('┌', theme.hi(), theme.bg())
+    fn top_right (colors: &BorderColors) -> BorderChar {
+        ('┐', colors.hi, colors.bg)
     }
-    fn top_right (theme: &impl BorderTheme) -> BorderChar {
-        ('┐', theme.hi(), theme.bg())
+    fn left (colors: &BorderColors) -> BorderChar {
+        ('│', colors.hi, colors.bg)
     }
-    fn left (theme: &impl BorderTheme) -> BorderChar {
-        ('│', theme.hi(), theme.bg())
+    fn right (colors: &BorderColors) -> BorderChar {
+        ('│', colors.hi, colors.bg)
     }
-    fn right (theme: &impl BorderTheme) -> BorderChar {
-        ('│', theme.hi(), theme.bg())
+    fn bottom (colors: &BorderColors) -> BorderChar {
+        ('─', colors.hi, colors.bg)
     }
-    fn bottom (theme: &impl BorderTheme) -> BorderChar {
-        ('─', theme.hi(), theme.bg())
+    fn bottom_left (colors: &BorderColors) -> BorderChar {
+        ('└', colors.hi, colors.bg)
     }
-    fn bottom_left (theme: &impl BorderTheme) -> BorderChar {
-        ('└', theme.hi(), theme.bg())
+    fn bottom_right (colors: &BorderColors) -> BorderChar {
+        ('┘', colors.hi, colors.bg)
     }
-    fn bottom_right (theme: &impl BorderTheme) -> BorderChar {
-        ('┘', theme.hi(), theme.bg())
+}
+
+impl<T> Tabbed<T> {
+    /// The focused tab is drawn in bold, in the theme's focus color.
+    fn highlight <W: Write> (engine: &TUI<W>) -> Style {
+        Style { fg: engine.theme.focus, bold: true, ..Style::default() }
     }
 }
 
 impl<W: Write, T: Output<TUI<W>, [u16;2]>> Output<TUI<W>, [u16;2]> for Tabbed<T> {
     fn render (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
         //self.scroll.size.set(engine.area.h() as usize); // Record the height for scrolling
-        self.layout().render(engine)
+        self.layout(Self::highlight(engine)).render(engine)
+    }
+    fn measure (&self, engine: &mut TUI<W>) -> Result<Option<[u16;2]>> {
+        self.layout(Self::highlight(engine)).measure(engine)
     }
 }
 